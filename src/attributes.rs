@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! SystemVerilog attributes (IEEE 1800-2017 section 5.12) on modules,
+//! instances, ports, parameters and signals.
+
+use crate::ConstValue;
+use serde_json::Value;
+
+/// An attribute name and its value, e.g. `("mark_debug", Some(1'b1))` for
+/// `(* mark_debug *)`.
+pub type Attribute = (String, Option<ConstValue>);
+
+/// Reads the attribute instances (`(* keep, mode = "fast" *)`) that Slang
+/// attaches to a symbol, in source order.
+///
+/// An attribute written without a value has the value 1, as in the standard.
+/// The value is `None` only if Slang does not report one that can be parsed.
+pub(crate) fn extract_attributes(value: &Value) -> Vec<Attribute> {
+    let Some(attributes) = value.get("attributes").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    attributes
+        .iter()
+        .filter_map(|attribute| {
+            let name = attribute.get("name")?.as_str()?;
+            let value = attribute
+                .get("value")
+                .and_then(Value::as_str)
+                .and_then(|value| value.parse().ok());
+            Some((name.to_string(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_attributes() {
+        let symbol = json!({
+            "name": "u_core",
+            "kind": "Instance",
+            "attributes": [
+                { "name": "keep", "kind": "Attribute", "value": "1'b1" },
+                { "name": "mode", "kind": "Attribute", "value": "\"fast\"" },
+                { "name": "weird", "kind": "Attribute" }
+            ]
+        });
+        assert_eq!(
+            extract_attributes(&symbol),
+            vec![
+                ("keep".to_string(), Some(ConstValue::integer(1, false, 1))),
+                (
+                    "mode".to_string(),
+                    Some(ConstValue::String("fast".to_string()))
+                ),
+                ("weird".to_string(), None),
+            ]
+        );
+        assert!(extract_attributes(&json!({ "name": "u_core" })).is_empty());
+    }
+}
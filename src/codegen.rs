@@ -0,0 +1,251 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers shared by the code generators that render package contents in
+//! other languages.
+
+use crate::Package;
+use crate::extract::{Range, Type, parse_integer_literal};
+use num_bigint::BigInt;
+use std::error::Error;
+
+/// An integer package parameter.
+pub(crate) struct Constant<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) width: usize,
+    pub(crate) signed: bool,
+    pub(crate) value: BigInt,
+}
+
+/// The contents of a package, split into constants and typedefs, each sorted
+/// by name so that generated output is deterministic.
+pub(crate) struct PackageItems<'a> {
+    pub(crate) constants: Vec<Constant<'a>>,
+    pub(crate) typedefs: Vec<(&'a str, Type)>,
+}
+
+impl<'a> PackageItems<'a> {
+    /// Collects the integer constants and typedefs of a package, sorted by
+    /// name.
+    ///
+    /// `language` names the target in the error returned for parameters with
+    /// other values (strings, reals) unless `skip_unsupported` is set, in
+    /// which case they are omitted.
+    pub(crate) fn new(
+        package: &'a Package,
+        language: &str,
+        skip_unsupported: bool,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut names: Vec<&String> = package.parameters.keys().collect();
+        names.sort();
+
+        let mut constants = Vec::new();
+        for name in names {
+            let value = &package.parameters[name].value;
+            if let Some((width, signed, value)) = parse_integer_literal(value) {
+                constants.push(Constant {
+                    name,
+                    width,
+                    signed,
+                    value,
+                });
+            } else if !skip_unsupported {
+                Err(format!(
+                    "Parameter `{name}` has a value that cannot be represented in {language}: {value}"
+                ))?;
+            }
+        }
+
+        let mut typedefs: Vec<(&str, Type)> = package
+            .typedefs
+            .iter()
+            .map(|(name, ty)| (name.as_str(), ty.clone()))
+            .collect();
+        typedefs.sort_by(|a, b| a.0.cmp(b.0));
+
+        Ok(Self {
+            constants,
+            typedefs,
+        })
+    }
+}
+
+/// Where a struct, union or enum has already been given a name.
+pub(crate) enum Nominal {
+    /// The definition has been emitted under this name.
+    Defined(String),
+    /// A typedef reserved this name but its definition is not yet emitted.
+    Claimed(String, Type),
+}
+
+/// Records the nominal types of a package as they are emitted, so that each
+/// struct, union or enum is defined once and before its first use.
+#[derive(Default)]
+pub(crate) struct NominalTypes {
+    defined: Vec<(String, Type)>,
+    claimed: Vec<(String, Type)>,
+}
+
+impl NominalTypes {
+    /// Reserves `name` for every nominal typedef among `typedefs`, so that
+    /// array typedefs of the same struct or enum refer to it rather than
+    /// introducing an anonymous copy.
+    pub(crate) fn claim_all(&mut self, typedefs: &[(&str, Type)], rename: impl Fn(&str) -> String) {
+        for (name, ty) in typedefs {
+            if is_nominal_definition(name, ty) {
+                self.claimed.push((rename(name), ty.clone()));
+            }
+        }
+    }
+
+    /// Marks `name` as emitted, returning false if it already was.
+    pub(crate) fn define(&mut self, name: &str, ty: &Type) -> bool {
+        if self.defined.iter().any(|(defined, _)| defined == name) {
+            return false;
+        }
+        self.claimed.retain(|(claimed, _)| claimed != name);
+        self.defined.push((name.to_string(), ty.clone()));
+        true
+    }
+
+    /// Finds an emitted or claimed type with the same members as `ty`.
+    pub(crate) fn lookup(&self, ty: &Type) -> Option<Nominal> {
+        if let Some((name, _)) = self
+            .defined
+            .iter()
+            .find(|(_, other)| same_members(ty, other))
+        {
+            return Some(Nominal::Defined(name.clone()));
+        }
+        self.claimed
+            .iter()
+            .find(|(_, other)| same_members(ty, other))
+            .map(|(name, ty)| Nominal::Claimed(name.clone(), ty.clone()))
+    }
+}
+
+/// Compares two nominal types by their members alone, disregarding their
+/// names and dimensions.
+fn same_members(a: &Type, b: &Type) -> bool {
+    match (a, b) {
+        (Type::Struct { fields: a, .. }, Type::Struct { fields: b, .. })
+        | (Type::Union { fields: a, .. }, Type::Union { fields: b, .. }) => a == b,
+        (Type::Enum { variants: a, .. }, Type::Enum { variants: b, .. }) => a == b,
+        _ => false,
+    }
+}
+
+/// A typedef whose target is a struct, union or enum declared under the same
+/// name, as opposed to an array of (or alias for) some other nominal type.
+pub(crate) fn is_nominal_definition(name: &str, ty: &Type) -> bool {
+    match ty {
+        Type::Struct { name: nominal, .. }
+        | Type::Union { name: nominal, .. }
+        | Type::Enum { name: nominal, .. } => {
+            local_name(nominal) == name
+                && ty.packed_dimensions().is_empty()
+                && ty.unpacked_dimensions().is_empty()
+        }
+        Type::Logic { .. } => false,
+    }
+}
+
+/// Returns `ty` without its packed and unpacked dimensions.
+pub(crate) fn strip_dimensions(ty: &Type) -> Type {
+    let mut ty = ty.clone();
+    let (packed_dimensions, unpacked_dimensions) = ty.dimensions_mut();
+    packed_dimensions.clear();
+    unpacked_dimensions.clear();
+    ty
+}
+
+/// Returns the packed dimensions of `ty` that index elements, and the width of
+/// each element. For bit vectors the innermost packed dimension is the element
+/// width rather than an array dimension.
+pub(crate) fn packed_elements(ty: &Type) -> Result<(&[Range], usize), Box<dyn Error>> {
+    let packed = ty.packed_dimensions();
+    match ty {
+        Type::Logic { .. } => Ok(match packed.split_last() {
+            Some((last, outer)) => (outer, range_length(last)),
+            None => (&[], 1),
+        }),
+        _ => Ok((&packed[..], strip_dimensions(ty).width()?)),
+    }
+}
+
+pub(crate) fn range_length(range: &Range) -> usize {
+    ((range.msb - range.lsb).abs() + 1) as usize
+}
+
+/// Drops any package or scope qualification from a SystemVerilog name.
+pub(crate) fn local_name(name: &str) -> &str {
+    let name = name.rsplit_once("::").map_or(name, |(_, local)| local);
+    name.rsplit_once('.').map_or(name, |(_, local)| local)
+}
+
+/// Returns the package qualifying `name`, if any.
+pub(crate) fn package_name(name: &str) -> Option<&str> {
+    name.rsplit_once("::").map(|(package, _)| package)
+}
+
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Maps a SystemVerilog identifier to a C-like identifier that is not one of
+/// `keywords`.
+pub(crate) fn identifier(name: &str, keywords: &[&str]) -> String {
+    let mut result: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if result.is_empty() || result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, '_');
+    }
+    if keywords.contains(&result.as_str()) {
+        result.push('_');
+    }
+    result
+}
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use crate::extract::parse_integer_literal;
+    use crate::{Package, Parameter, parse_type_definition};
+    use std::collections::HashMap;
+
+    /// Builds a package from `(name, value)` pairs, where values that are
+    /// types rather than integer literals become typedefs.
+    pub(crate) fn package(name: &str, items: &[(&str, &str)]) -> Package {
+        let mut package = Package {
+            name: name.to_string(),
+            parameters: HashMap::new(),
+            typedefs: HashMap::new(),
+            enums: HashMap::new(),
+            subroutines: HashMap::new(),
+            imports: Vec::new(),
+            location: None,
+        };
+        for (name, value) in items {
+            if parse_integer_literal(value).is_none() {
+                if let Ok(ty) = parse_type_definition(value) {
+                    package.typedefs.insert(name.to_string(), ty);
+                    continue;
+                }
+            }
+            package.parameters.insert(
+                name.to_string(),
+                Parameter {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                    ty: None,
+                    location: None,
+                },
+            );
+        }
+        package
+    }
+}
@@ -0,0 +1,469 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Extraction of the port connections of an instance.
+//!
+//! Slang serializes what each port of an instance is connected to, but not
+//! how the connection was written. On request, the connection list is read
+//! back from the source file to tell named, implicit, wildcard and positional
+//! connections apart; see [`crate::Hierarchy::read_connection_styles`].
+
+use crate::PortDir;
+use crate::expression::{link_name, render_expression};
+use crate::location::SourceLocation;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PortConnection {
+    pub port: String,
+    /// The direction of the port, or `None` for an interface port.
+    pub dir: Option<PortDir>,
+    /// The connected expression, or `None` if the port is unconnected.
+    pub expression: Option<String>,
+    /// The signal or interface instance the port is connected to, if the
+    /// expression is a plain reference such as `.d(data)` rather than, say,
+    /// `.d(data[3:0])`.
+    pub target: Option<String>,
+    /// How the connection is written. Slang does not report it, so it is
+    /// `None` unless read from the source with
+    /// [`crate::Hierarchy::read_connection_styles`].
+    pub style: Option<ConnectionStyle>,
+}
+
+impl PortConnection {
+    pub fn is_connected(&self) -> bool {
+        self.expression.is_some()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ConnectionStyle {
+    /// `.port(expr)`, including `.port()`.
+    Named,
+    /// `.port`, connected to the signal of the same name.
+    ImplicitNamed,
+    /// Connected by `.*`.
+    Wildcard,
+    /// Connected by position in an ordered list.
+    Positional,
+}
+
+/// Reads the files that instances are declared in, each at most once.
+#[derive(Default)]
+pub(crate) struct SourceTexts {
+    files: HashMap<String, Option<String>>,
+}
+
+impl SourceTexts {
+    fn get(&mut self, file: &str) -> Option<&str> {
+        self.files
+            .entry(file.to_string())
+            .or_insert_with(|| std::fs::read_to_string(file).ok())
+            .as_deref()
+    }
+}
+
+/// Extracts the connections of an `Instance` node, in port order. `resolve`
+/// follows links to the serialized port symbols.
+pub(crate) fn extract_connections<'a>(
+    instance: &'a Value,
+    resolve: impl Fn(&'a Value) -> &'a Value,
+) -> Vec<PortConnection> {
+    let Some(connections) = instance["connections"].as_array() else {
+        return Vec::new();
+    };
+    connections
+        .iter()
+        .filter_map(|connection| {
+            let port = resolve(&connection["port"]);
+            let name = match port {
+                Value::Object(_) => port["name"].as_str(),
+                _ => link_name(port),
+            }?;
+            let dir = port["direction"]
+                .as_str()
+                .and_then(|dir| PortDir::from_str(dir).ok());
+            let (expression, target) = if connection["ifaceInstance"].is_null() {
+                connected_expression(&connection["expr"])
+            } else {
+                let instance = link_name(&connection["ifaceInstance"]).map(str::to_string);
+                (instance.clone(), instance)
+            };
+            Some(PortConnection {
+                port: name.to_string(),
+                dir,
+                expression,
+                target,
+                style: None,
+            })
+        })
+        .collect()
+}
+
+/// Sets the style of the connections of the instance at `location` from its
+/// connection list in the source, leaving them unchanged if the list cannot
+/// be read there.
+pub(crate) fn read_styles(
+    connections: &mut [PortConnection],
+    location: &SourceLocation,
+    sources: &mut SourceTexts,
+) {
+    let listed = sources
+        .get(&location.file)
+        .and_then(|text| listed_connections(text, offset(text, location)?));
+    if let Some(listed) = listed {
+        apply_styles(connections, &listed);
+    }
+}
+
+/// Renders a connection expression and, if it is a plain reference, the name
+/// it refers to. Slang wraps the connections of output and inout ports in an
+/// assignment whose left-hand side is the connected expression.
+fn connected_expression(expr: &Value) -> (Option<String>, Option<String>) {
+    let mut expr = expr;
+    loop {
+        match expr["kind"].as_str() {
+            Some("Assignment") => expr = &expr["left"],
+            Some("Conversion") => expr = &expr["operand"],
+            _ => break,
+        }
+    }
+    match expr["kind"].as_str() {
+        None | Some("EmptyArgument") => (None, None),
+        Some("NamedValue") | Some("HierarchicalValue") | Some("ArbitrarySymbol") => {
+            let name = link_name(&expr["symbol"]).map(str::to_string);
+            (name.clone(), name)
+        }
+        Some(_) => (render_expression(expr), None),
+    }
+}
+
+/// One entry of the connection list of an instance, as written in the source.
+#[derive(Debug, PartialEq)]
+enum Listed {
+    Named(String),
+    ImplicitNamed(String),
+    Wildcard,
+    Positional,
+}
+
+fn apply_styles(connections: &mut [PortConnection], listed: &[Listed]) {
+    let has_wildcard = listed.contains(&Listed::Wildcard);
+    let mut positional = 0;
+    for entry in listed {
+        let (index, style) = match entry {
+            Listed::Named(name) => (
+                connections.iter().position(|c| &c.port == name),
+                ConnectionStyle::Named,
+            ),
+            Listed::ImplicitNamed(name) => (
+                connections.iter().position(|c| &c.port == name),
+                ConnectionStyle::ImplicitNamed,
+            ),
+            Listed::Positional => {
+                positional += 1;
+                (Some(positional - 1), ConnectionStyle::Positional)
+            }
+            Listed::Wildcard => continue,
+        };
+        if let Some(connection) = index.and_then(|index| connections.get_mut(index)) {
+            connection.style = Some(style);
+        }
+    }
+    if has_wildcard {
+        for connection in connections.iter_mut() {
+            if connection.style.is_none() {
+                connection.style = Some(ConnectionStyle::Wildcard);
+            }
+        }
+    }
+}
+
+/// Converts a one-based line and column into a byte offset into `text`.
+fn offset(text: &str, location: &SourceLocation) -> Option<usize> {
+    let line_start = if location.line == 1 {
+        0
+    } else {
+        text.match_indices('\n').nth(location.line - 2)?.0 + 1
+    };
+    let offset = line_start + location.column.checked_sub(1)?;
+    (offset <= text.len()).then_some(offset)
+}
+
+/// Reads the connection list that follows the instance name at `start`,
+/// skipping any unpacked dimensions of an instance array.
+fn listed_connections(text: &str, start: usize) -> Option<Vec<Listed>> {
+    let mut scanner = Scanner {
+        text: text.as_bytes(),
+        pos: start,
+    };
+    scanner.identifier()?;
+    loop {
+        scanner.skip_trivia();
+        match scanner.peek()? {
+            b'[' => scanner.skip_balanced(b'[', b']')?,
+            b'(' => break,
+            _ => return None,
+        }
+    }
+    scanner.pos += 1;
+
+    let mut listed = Vec::new();
+    loop {
+        scanner.skip_trivia();
+        let entry = match scanner.peek()? {
+            b')' if listed.is_empty() => return Some(listed),
+            b'.' => {
+                scanner.pos += 1;
+                scanner.skip_trivia();
+                if scanner.peek()? == b'*' {
+                    scanner.pos += 1;
+                    Listed::Wildcard
+                } else {
+                    let name = scanner.identifier()?;
+                    scanner.skip_trivia();
+                    if scanner.peek()? == b'(' {
+                        scanner.skip_balanced(b'(', b')')?;
+                        Listed::Named(name)
+                    } else {
+                        Listed::ImplicitNamed(name)
+                    }
+                }
+            }
+            _ => {
+                scanner.skip_expression()?;
+                Listed::Positional
+            }
+        };
+        listed.push(entry);
+        scanner.skip_trivia();
+        match scanner.peek()? {
+            b',' => scanner.pos += 1,
+            b')' => return Some(listed),
+            _ => return None,
+        }
+    }
+}
+
+struct Scanner<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    fn identifier(&mut self) -> Option<String> {
+        let start = self.pos;
+        if self.peek()? == b'\\' {
+            // An escaped identifier ends at the next whitespace.
+            while self.peek().is_some_and(|c| !c.is_ascii_whitespace()) {
+                self.pos += 1;
+            }
+            return Some(String::from_utf8_lossy(&self.text[start + 1..self.pos]).into_owned());
+        }
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == b'_' || c == b'$')
+        {
+            self.pos += 1;
+        }
+        (self.pos > start)
+            .then(|| String::from_utf8_lossy(&self.text[start..self.pos]).into_owned())
+    }
+
+    /// Skips whitespace and comments.
+    fn skip_trivia(&mut self) {
+        loop {
+            while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+                self.pos += 1;
+            }
+            if self.text[self.pos.min(self.text.len())..].starts_with(b"//") {
+                while self.peek().is_some_and(|c| c != b'\n') {
+                    self.pos += 1;
+                }
+            } else if self.text[self.pos.min(self.text.len())..].starts_with(b"/*") {
+                self.pos += 2;
+                while self.pos < self.text.len() && !self.text[self.pos..].starts_with(b"*/") {
+                    self.pos += 1;
+                }
+                self.pos += 2;
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Skips from an opening bracket to just past its matching closing one.
+    fn skip_balanced(&mut self, open: u8, close: u8) -> Option<()> {
+        let mut depth = 0;
+        loop {
+            self.skip_trivia();
+            let c = self.peek()?;
+            self.pos += 1;
+            if c == b'"' {
+                self.skip_string()?;
+            } else if c == open {
+                depth += 1;
+            } else if c == close {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(());
+                }
+            }
+        }
+    }
+
+    /// Skips a positional connection up to the `,` or `)` that ends it.
+    fn skip_expression(&mut self) -> Option<()> {
+        loop {
+            self.skip_trivia();
+            match self.peek()? {
+                b',' | b')' => return Some(()),
+                b'(' => self.skip_balanced(b'(', b')')?,
+                b'[' => self.skip_balanced(b'[', b']')?,
+                b'{' => self.skip_balanced(b'{', b'}')?,
+                b'"' => {
+                    self.pos += 1;
+                    self.skip_string()?;
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    /// Skips the rest of a string literal whose opening quote was consumed.
+    fn skip_string(&mut self) -> Option<()> {
+        loop {
+            let c = self.peek()?;
+            self.pos += 1;
+            match c {
+                b'\\' => self.pos += 1,
+                b'"' => return Some(()),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_connection_list_styles() {
+        let text = "module top;\n  fifo #(.W(8)) u_fifo [1:0] (.clk, .d(a[3:0] /* ) */), .*);\n  \
+                    leaf u_leaf (x, {y, z}, );\nendmodule\n";
+        let location = |line, column| SourceLocation {
+            file: String::new(),
+            line,
+            column,
+        };
+
+        let fifo = offset(text, &location(2, 17)).unwrap();
+        assert_eq!(
+            listed_connections(text, fifo).unwrap(),
+            vec![
+                Listed::ImplicitNamed("clk".to_string()),
+                Listed::Named("d".to_string()),
+                Listed::Wildcard,
+            ]
+        );
+
+        let leaf = offset(text, &location(3, 8)).unwrap();
+        assert_eq!(
+            listed_connections(text, leaf).unwrap(),
+            vec![Listed::Positional, Listed::Positional, Listed::Positional]
+        );
+    }
+
+    /// Resolves links to the given port symbols by address.
+    fn resolver<'a>(ports: &'a [Value]) -> impl Fn(&'a Value) -> &'a Value {
+        move |value| {
+            let address = value
+                .as_str()
+                .and_then(|link| link.split(' ').next()?.parse::<u64>().ok());
+            ports
+                .iter()
+                .find(|port| port["addr"].as_u64() == address)
+                .unwrap_or(value)
+        }
+    }
+
+    #[test]
+    fn extracts_connections() {
+        let ports = json!([
+            { "kind": "Port", "name": "clk", "addr": 1, "direction": "In" },
+            { "kind": "Port", "name": "d", "addr": 2, "direction": "In" },
+            { "kind": "Port", "name": "q", "addr": 3, "direction": "Out" },
+            { "kind": "Port", "name": "unused", "addr": 4, "direction": "In" },
+            { "kind": "InterfacePort", "name": "bus", "addr": 5 }
+        ]);
+        let instance = json!({
+            "kind": "Instance",
+            "name": "u_fifo",
+            "connections": [
+                {
+                    "port": "1 clk",
+                    "expr": { "kind": "NamedValue", "symbol": "10 clk" }
+                },
+                {
+                    "port": "2 d",
+                    "expr": {
+                        "kind": "RangeSelect",
+                        "selectionKind": "Simple",
+                        "value": { "kind": "NamedValue", "symbol": "11 a" },
+                        "left": { "kind": "IntegerLiteral", "value": "3" },
+                        "right": { "kind": "IntegerLiteral", "value": "0" }
+                    }
+                },
+                {
+                    "port": "3 q",
+                    "expr": {
+                        "kind": "Assignment",
+                        "left": { "kind": "NamedValue", "symbol": "12 q_out" },
+                        "right": { "kind": "EmptyArgument" }
+                    }
+                },
+                { "port": "4 unused" },
+                { "port": "5 bus", "ifaceInstance": "13 u_bus" }
+            ]
+        });
+        let ports = ports.as_array().unwrap();
+        let connections = extract_connections(&instance, resolver(ports));
+        assert_eq!(
+            connections[0],
+            PortConnection {
+                port: "clk".to_string(),
+                dir: Some(PortDir::Input),
+                expression: Some("clk".to_string()),
+                target: Some("clk".to_string()),
+                style: None,
+            }
+        );
+        assert_eq!(connections[1].expression.as_deref(), Some("a[3:0]"));
+        assert_eq!(connections[1].target, None);
+        assert_eq!(connections[2].dir, Some(PortDir::Output));
+        assert_eq!(connections[2].target.as_deref(), Some("q_out"));
+        assert!(!connections[3].is_connected());
+        assert_eq!(connections[4].dir, None);
+        assert_eq!(connections[4].target.as_deref(), Some("u_bus"));
+
+        let mut styled = connections.clone();
+        apply_styles(
+            &mut styled,
+            &[
+                Listed::ImplicitNamed("clk".to_string()),
+                Listed::Named("d".to_string()),
+                Listed::Wildcard,
+            ],
+        );
+        assert_eq!(styled[0].style, Some(ConnectionStyle::ImplicitNamed));
+        assert_eq!(styled[1].style, Some(ConnectionStyle::Named));
+        assert_eq!(styled[2].style, Some(ConnectionStyle::Wildcard));
+    }
+}
@@ -0,0 +1,485 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Constant values as printed by Slang, e.g. for attributes and parameters.
+
+use crate::extract::{Type, parse_integer_literal};
+use num_bigint::{BigInt, BigUint};
+use num_traits::{ToPrimitive, Zero};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A constant value computed by Slang.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConstValue {
+    /// An integral value such as `8'hff`, `4'sb1010` or `2'b1x`.
+    Integer {
+        width: usize,
+        signed: bool,
+        /// The value of the known bits, interpreted according to `signed`.
+        /// X and Z bits read as 0.
+        value: BigInt,
+        /// A mask of the bits that are X.
+        x_bits: BigUint,
+        /// A mask of the bits that are Z.
+        z_bits: BigUint,
+    },
+    Real(f64),
+    String(String),
+    /// An unpacked array, or an unpacked struct whose field names are not
+    /// known, with its elements from the left bound to the right bound.
+    Array(Vec<ConstValue>),
+    /// An unpacked struct, with its fields in declaration order.
+    Struct(Vec<(String, ConstValue)>),
+}
+
+/// Explains why a constant could not be parsed or converted, e.g.
+/// `integer 2 has X or Z bits`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConstValueError {
+    message: String,
+}
+
+impl ConstValueError {
+    pub(crate) fn new(message: String) -> Self {
+        ConstValueError { message }
+    }
+}
+
+impl fmt::Display for ConstValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for ConstValueError {}
+
+impl ConstValue {
+    /// Makes a two-state integer.
+    pub fn integer(width: usize, signed: bool, value: impl Into<BigInt>) -> Self {
+        ConstValue::Integer {
+            width,
+            signed,
+            value: value.into(),
+            x_bits: BigUint::zero(),
+            z_bits: BigUint::zero(),
+        }
+    }
+
+    /// Names the fields of unpacked structs within `self`, which Slang prints
+    /// as plain lists, using the declared type of the value.
+    pub(crate) fn with_type(self, ty: &Type) -> ConstValue {
+        let ConstValue::Array(elements) = self else {
+            return self;
+        };
+        if !ty.unpacked_dimensions().is_empty() {
+            let mut element_type = ty.clone();
+            element_type.dimensions_mut().1.remove(0);
+            return ConstValue::Array(
+                elements
+                    .into_iter()
+                    .map(|element| element.with_type(&element_type))
+                    .collect(),
+            );
+        }
+        match ty {
+            Type::Struct {
+                packed: false,
+                fields,
+                ..
+            } if fields.len() == elements.len() => ConstValue::Struct(
+                fields
+                    .iter()
+                    .zip(elements)
+                    .map(|(field, element)| (field.name.clone(), element.with_type(&field.ty)))
+                    .collect(),
+            ),
+            _ => ConstValue::Array(elements),
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            ConstValue::Integer { .. } => "an integer",
+            ConstValue::Real(_) => "a real",
+            ConstValue::String(_) => "a string",
+            ConstValue::Array(_) => "an array",
+            ConstValue::Struct(_) => "a struct",
+        }
+    }
+}
+
+impl FromStr for ConstValue {
+    type Err = ConstValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { text: s, pos: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos != s.len() {
+            return Err(parser.error());
+        }
+        Ok(value)
+    }
+}
+
+impl TryFrom<&ConstValue> for BigInt {
+    type Error = ConstValueError;
+
+    fn try_from(value: &ConstValue) -> Result<Self, Self::Error> {
+        match value {
+            ConstValue::Integer {
+                value,
+                x_bits,
+                z_bits,
+                ..
+            } => {
+                if !x_bits.is_zero() || !z_bits.is_zero() {
+                    return Err(ConstValueError::new(format!(
+                        "integer {value} has X or Z bits"
+                    )));
+                }
+                Ok(value.clone())
+            }
+            other => Err(ConstValueError::new(format!(
+                "expected an integer, found {}",
+                other.describe()
+            ))),
+        }
+    }
+}
+
+impl TryFrom<&ConstValue> for u64 {
+    type Error = ConstValueError;
+
+    fn try_from(value: &ConstValue) -> Result<Self, Self::Error> {
+        let integer = BigInt::try_from(value)?;
+        integer
+            .to_u64()
+            .ok_or_else(|| ConstValueError::new(format!("integer {integer} does not fit in u64")))
+    }
+}
+
+impl TryFrom<&ConstValue> for f64 {
+    type Error = ConstValueError;
+
+    fn try_from(value: &ConstValue) -> Result<Self, Self::Error> {
+        match value {
+            ConstValue::Real(real) => Ok(*real),
+            ConstValue::Integer { .. } => {
+                let integer = BigInt::try_from(value)?;
+                integer.to_f64().ok_or_else(|| {
+                    ConstValueError::new(format!("integer {integer} does not fit in f64"))
+                })
+            }
+            other => Err(ConstValueError::new(format!(
+                "expected a number, found {}",
+                other.describe()
+            ))),
+        }
+    }
+}
+
+/// Parses Slang's rendering of a constant, where arrays and unpacked structs
+/// are printed as `[1,2]`, and also assignment patterns such as `'{a:1, b:2}`.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn error(&self) -> ConstValueError {
+        ConstValueError::new(format!("unsupported constant value: {}", self.text.trim()))
+    }
+
+    fn value(&mut self) -> Result<ConstValue, ConstValueError> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let (open, close) = if rest.starts_with("'{") {
+            (2, '}')
+        } else if rest.starts_with('[') {
+            (1, ']')
+        } else if rest.starts_with('{') {
+            (1, '}')
+        } else {
+            return self.scalar();
+        };
+        self.pos += open;
+
+        let mut elements = Vec::new();
+        let mut names = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with(close) && elements.is_empty() {
+                break;
+            }
+            if let Some(name) = self.key() {
+                names.push(name);
+            }
+            elements.push(self.value()?);
+            self.skip_whitespace();
+            if self.rest().starts_with(',') {
+                self.pos += 1;
+            } else if self.rest().starts_with(close) {
+                break;
+            } else {
+                return Err(self.error());
+            }
+        }
+        self.pos += 1;
+
+        if names.is_empty() {
+            Ok(ConstValue::Array(elements))
+        } else if names.len() == elements.len() {
+            Ok(ConstValue::Struct(
+                names.into_iter().zip(elements).collect(),
+            ))
+        } else {
+            Err(self.error())
+        }
+    }
+
+    /// Consumes a `name:` member key if there is one.
+    fn key(&mut self) -> Option<String> {
+        let rest = self.rest();
+        let length = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+            .unwrap_or(rest.len());
+        let (name, after) = rest.split_at(length);
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            return None;
+        }
+        let after_colon = after.trim_start().strip_prefix(':')?;
+        self.pos = self.text.len() - after_colon.len();
+        Some(name.to_string())
+    }
+
+    fn scalar(&mut self) -> Result<ConstValue, ConstValueError> {
+        let rest = self.rest();
+        let length = if rest.starts_with('"') {
+            let mut escaped = false;
+            rest.char_indices()
+                .skip(1)
+                .find(|&(_, c)| {
+                    let end = c == '"' && !escaped;
+                    escaped = c == '\\' && !escaped;
+                    end
+                })
+                .map(|(index, _)| index + 1)
+                .ok_or_else(|| self.error())?
+        } else {
+            rest.find([',', ']', '}']).unwrap_or(rest.len())
+        };
+        self.pos += length;
+        parse_scalar(rest[..length].trim()).ok_or_else(|| self.error())
+    }
+}
+
+fn parse_scalar(s: &str) -> Option<ConstValue> {
+    if let Some(text) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        return Some(ConstValue::String(unescape(text)));
+    }
+    if let Some((width, signed, value)) = parse_integer_literal(s) {
+        return Some(ConstValue::integer(width, signed, value));
+    }
+    if s.contains('\'') {
+        return parse_four_state(s);
+    }
+    s.parse::<f64>().ok().map(ConstValue::Real)
+}
+
+/// Parses a sized integer literal containing X or Z digits, such as `4'b1x0z`
+/// or `8'hzz`. A decimal literal can only be entirely X or Z (`8'dx`).
+fn parse_four_state(s: &str) -> Option<ConstValue> {
+    let (width, digits) = s.split_once('\'')?;
+    let width = width.parse::<usize>().ok()?;
+    let (signed, digits) = match digits.strip_prefix('s') {
+        Some(digits) => (true, digits),
+        None => (false, digits),
+    };
+    let mut chars = digits.chars();
+    let bits_per_digit = match chars.next()? {
+        'b' => 1,
+        'o' => 3,
+        'h' => 4,
+        'd' => 0,
+        _ => return None,
+    };
+    let digits = chars.as_str().replace('_', "").to_ascii_lowercase();
+    let all = (BigUint::from(1u8) << width) - 1u8;
+
+    let (mut value, mut x_bits, mut z_bits) = (BigUint::zero(), BigUint::zero(), BigUint::zero());
+    if bits_per_digit == 0 {
+        match digits.as_str() {
+            "x" => x_bits = all.clone(),
+            "z" | "?" => z_bits = all.clone(),
+            _ => return None,
+        }
+    } else {
+        let digit_mask = BigUint::from((1u8 << bits_per_digit) - 1);
+        for digit in digits.chars() {
+            value <<= bits_per_digit;
+            x_bits <<= bits_per_digit;
+            z_bits <<= bits_per_digit;
+            match digit {
+                'x' => x_bits |= &digit_mask,
+                'z' | '?' => z_bits |= &digit_mask,
+                _ => value |= BigUint::from(digit.to_digit(1 << bits_per_digit)?),
+            }
+        }
+    }
+    let (value, x_bits, z_bits) = (value & &all, x_bits & &all, z_bits & &all);
+
+    let mut value = BigInt::from(value);
+    if signed && width > 0 && value.bit((width - 1) as u64) {
+        value -= BigInt::from(1u8) << width;
+    }
+    Some(ConstValue::Integer {
+        width,
+        signed,
+        value,
+        x_bits,
+        z_bits,
+    })
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::parse_type_definition;
+
+    #[test]
+    fn parses_integers() {
+        assert_eq!("42".parse(), Ok(ConstValue::integer(32, true, 42)));
+        assert_eq!("8'hff".parse(), Ok(ConstValue::integer(8, false, 255)));
+        assert_eq!("4'sb1010".parse(), Ok(ConstValue::integer(4, true, -6)));
+        assert_eq!(
+            "4'b1x0z".parse(),
+            Ok(ConstValue::Integer {
+                width: 4,
+                signed: false,
+                value: BigInt::from(8),
+                x_bits: BigUint::from(4u8),
+                z_bits: BigUint::from(1u8),
+            })
+        );
+        assert_eq!(
+            "6'hzx".parse(),
+            Ok(ConstValue::Integer {
+                width: 6,
+                signed: false,
+                value: BigInt::zero(),
+                x_bits: BigUint::from(0b1111u8),
+                z_bits: BigUint::from(0b110000u8),
+            })
+        );
+        assert_eq!(
+            "3'dx".parse::<ConstValue>().unwrap(),
+            ConstValue::Integer {
+                width: 3,
+                signed: false,
+                value: BigInt::zero(),
+                x_bits: BigUint::from(7u8),
+                z_bits: BigUint::zero(),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_reals_and_strings() {
+        assert_eq!("1.5".parse(), Ok(ConstValue::Real(1.5)));
+        assert_eq!("2e-3".parse(), Ok(ConstValue::Real(0.002)));
+        assert_eq!(
+            r#""say \"hi\"""#.parse(),
+            Ok(ConstValue::String("say \"hi\"".to_string()))
+        );
+        assert!("8'hfg".parse::<ConstValue>().is_err());
+        assert!("foo".parse::<ConstValue>().is_err());
+    }
+
+    #[test]
+    fn parses_aggregates() {
+        let one = ConstValue::integer(32, true, 1);
+        let two = ConstValue::integer(32, true, 2);
+        assert_eq!(
+            "[1,2]".parse(),
+            Ok(ConstValue::Array(vec![one.clone(), two.clone()]))
+        );
+        assert_eq!(
+            "'{a:1, b:'{\"x,y\", 2}}".parse(),
+            Ok(ConstValue::Struct(vec![
+                ("a".to_string(), one.clone()),
+                (
+                    "b".to_string(),
+                    ConstValue::Array(vec![ConstValue::String("x,y".to_string()), two.clone()])
+                ),
+            ]))
+        );
+        assert_eq!("[]".parse(), Ok(ConstValue::Array(vec![])));
+        assert!("[1,2".parse::<ConstValue>().is_err());
+        assert!("[a:1, 2]".parse::<ConstValue>().is_err());
+
+        let ty =
+            parse_type_definition("struct{logic[3:0] a;logic[3:0]$[0:1] b;}pair_t$[0:0]").unwrap();
+        assert_eq!(
+            "[[1,[2,2]]]".parse::<ConstValue>().unwrap().with_type(&ty),
+            ConstValue::Array(vec![ConstValue::Struct(vec![
+                ("a".to_string(), one),
+                ("b".to_string(), ConstValue::Array(vec![two.clone(), two])),
+            ])])
+        );
+    }
+
+    #[test]
+    fn converts_cleanly() {
+        let byte: ConstValue = "8'hff".parse().unwrap();
+        assert_eq!(BigInt::try_from(&byte), Ok(BigInt::from(255)));
+        assert_eq!(u64::try_from(&byte), Ok(255));
+        assert_eq!(f64::try_from(&byte), Ok(255.0));
+
+        let negative: ConstValue = "4'sb1010".parse().unwrap();
+        assert_eq!(
+            u64::try_from(&negative).unwrap_err().to_string(),
+            "integer -6 does not fit in u64"
+        );
+        let unknown: ConstValue = "2'b1x".parse().unwrap();
+        assert_eq!(
+            u64::try_from(&unknown).unwrap_err().to_string(),
+            "integer 2 has X or Z bits"
+        );
+        assert_eq!(f64::try_from(&ConstValue::Real(0.5)), Ok(0.5));
+        assert_eq!(
+            BigInt::try_from(&ConstValue::Real(0.5))
+                .unwrap_err()
+                .to_string(),
+            "expected an integer, found a real"
+        );
+    }
+}
@@ -0,0 +1,658 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generation of C++ headers from extracted SystemVerilog packages.
+//!
+//! Package parameters become `constexpr` constants and enums become `enum
+//! class`es over the smallest fitting integer type. Structs and unions become
+//! classes that hold their bits in a `uint64_t words[]` array, least
+//! significant word first, using the same layout as a SystemVerilog packed
+//! type: the first struct field occupies the most significant bits. Each field
+//! has an inline getter named after the field and a `set_` setter; fields with
+//! packed dimensions take one `int` index per dimension, using the
+//! SystemVerilog index values.
+//!
+//! Types qualified with another package (`other_pkg::T`) are assumed to be
+//! generated into `other_pkg.h` and are included rather than redefined.
+
+use crate::Package;
+use crate::codegen::{
+    Nominal, NominalTypes, PackageItems, is_identifier, is_nominal_definition, local_name,
+    package_name, packed_elements, range_length,
+};
+use crate::extract::{Range, Type};
+use num_bigint::BigInt;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::Write;
+
+/// C++ keywords likely to collide with SystemVerilog identifiers, plus the
+/// names reserved by the generated classes.
+const CPP_KEYWORDS: &[&str] = &[
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "delete",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "explicit",
+    "export",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "friend",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "namespace",
+    "new",
+    "operator",
+    "private",
+    "protected",
+    "public",
+    "register",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "template",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typedef",
+    "typename",
+    "union",
+    "unsigned",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+    "kWidth",
+    "kWords",
+    "words",
+    "detail",
+];
+
+/// Bit manipulation helpers shared by all generated accessors.
+const DETAIL: &str = "namespace detail {
+
+inline uint64_t get_bits(const uint64_t* words, unsigned lsb, unsigned width) {
+  unsigned index = lsb / 64;
+  unsigned shift = lsb % 64;
+  uint64_t value = words[index] >> shift;
+  if (shift != 0 && shift + width > 64) {
+    value |= words[index + 1] << (64 - shift);
+  }
+  return width == 64 ? value : value & ((uint64_t{1} << width) - 1);
+}
+
+inline void set_bits(uint64_t* words, unsigned lsb, unsigned width, uint64_t value) {
+  uint64_t mask = width == 64 ? ~uint64_t{0} : (uint64_t{1} << width) - 1;
+  unsigned index = lsb / 64;
+  unsigned shift = lsb % 64;
+  value &= mask;
+  words[index] = (words[index] & ~(mask << shift)) | (value << shift);
+  if (shift != 0 && shift + width > 64) {
+    unsigned spill = 64 - shift;
+    words[index + 1] = (words[index + 1] & ~(mask >> spill)) | (value >> spill);
+  }
+}
+
+inline void copy_bits(uint64_t* dst, unsigned dst_lsb, const uint64_t* src, unsigned src_lsb,
+                      unsigned width) {
+  for (unsigned done = 0; done < width; done += 64) {
+    unsigned chunk = width - done < 64 ? width - done : 64;
+    set_bits(dst, dst_lsb + done, chunk, get_bits(src, src_lsb + done, chunk));
+  }
+}
+
+inline int64_t sign_extend(uint64_t value, unsigned width) {
+  uint64_t sign = uint64_t{1} << (width - 1);
+  return static_cast<int64_t>((value ^ sign) - sign);
+}
+
+}  // namespace detail
+";
+
+/// Renders the parameters and typedefs of `package` as a C++ header.
+///
+/// The header declares everything inside a namespace named after the package.
+/// Definitions are emitted in a deterministic order: constants sorted by name,
+/// followed by types sorted by name, with any struct, union or enum a type
+/// depends on emitted before its first use.
+///
+/// Parameters that cannot be represented as a C++ integer constant, such as
+/// strings, reals or integers wider than 64 bits, produce an error unless
+/// `skip_unsupported` is set, in which case they are omitted. Unpacked
+/// dimensions inside structs are always rejected because they have no packed
+/// layout.
+pub fn generate_cpp_header(
+    package: &Package,
+    skip_unsupported: bool,
+) -> Result<String, Box<dyn Error>> {
+    let items = PackageItems::new(package, "C++", skip_unsupported)?;
+
+    let mut constants = String::new();
+    for constant in items.constants.iter() {
+        if constant.width > 64 {
+            if skip_unsupported {
+                continue;
+            }
+            Err(format!(
+                "Parameter `{}` is wider than 64 bits and cannot be represented in C++",
+                constant.name
+            ))?;
+        }
+        writeln!(
+            constants,
+            "constexpr {} {} = {};",
+            integer_type(constant.width, constant.signed),
+            identifier(constant.name),
+            integer_literal(&constant.value, constant.width, constant.signed)
+        )?;
+    }
+
+    let mut emitter = CppEmitter::new(&package.name);
+    emitter.nominals.claim_all(&items.typedefs, identifier);
+    for (name, ty) in items.typedefs.iter() {
+        emitter.typedef(name, ty)?;
+    }
+
+    let namespace = identifier(&package.name);
+    let mut output = format!(
+        "// Generated from SystemVerilog package `{}`.\n\n#pragma once\n\n\
+         #include <array>\n#include <cstdint>\n",
+        package.name
+    );
+    for include in emitter.includes.iter() {
+        writeln!(output, "#include \"{include}.h\"")?;
+    }
+    write!(output, "\nnamespace {namespace} {{\n\n{DETAIL}")?;
+    if !constants.is_empty() {
+        write!(output, "\n{constants}")?;
+    }
+    for item in emitter.items {
+        write!(output, "\n{item}")?;
+    }
+    write!(output, "\n}}  // namespace {namespace}\n")?;
+    Ok(output)
+}
+
+/// Tracks the definitions emitted so far for one package.
+struct CppEmitter<'a> {
+    package: &'a str,
+    nominals: NominalTypes,
+    includes: BTreeSet<String>,
+    /// Rendered top-level definitions in dependency order.
+    items: Vec<String>,
+}
+
+impl<'a> CppEmitter<'a> {
+    fn new(package: &'a str) -> Self {
+        Self {
+            package,
+            nominals: NominalTypes::default(),
+            includes: BTreeSet::new(),
+            items: Vec::new(),
+        }
+    }
+
+    /// Emits a package-level typedef named `name`.
+    fn typedef(&mut self, name: &str, ty: &Type) -> Result<(), Box<dyn Error>> {
+        if is_nominal_definition(name, ty) {
+            return self.nominal(&identifier(name), ty);
+        }
+        let element = match ty {
+            Type::Logic { signed, .. } => {
+                let (outer, width) = packed_elements(ty)?;
+                let count: usize = outer.iter().map(range_length).product();
+                storage_type(width * count, *signed)
+            }
+            // Packed arrays of aggregates are represented element by element.
+            _ => nested_arrays(self.element(ty, name)?, ty.packed_dimensions()),
+        };
+        let rendered = nested_arrays(element, ty.unpacked_dimensions());
+        self.items
+            .push(format!("using {} = {rendered};\n", identifier(name)));
+        Ok(())
+    }
+
+    /// Returns the C++ name of a struct, union or enum element type, first
+    /// emitting its definition if needed. `context` names anonymous aggregates.
+    fn element(&mut self, ty: &Type, context: &str) -> Result<String, Box<dyn Error>> {
+        let (Type::Struct { name, .. } | Type::Union { name, .. } | Type::Enum { name, .. }) = ty
+        else {
+            unreachable!()
+        };
+        Ok(match (package_name(name), self.nominals.lookup(ty)) {
+            (Some(package), _) if package != self.package => {
+                self.includes.insert(package.to_string());
+                format!(
+                    "::{}::{}",
+                    identifier(package),
+                    identifier(local_name(name))
+                )
+            }
+            (_, Some(Nominal::Defined(existing))) => existing,
+            (_, Some(Nominal::Claimed(existing, claimed))) => {
+                self.nominal(&existing, &claimed)?;
+                existing
+            }
+            (_, None) => {
+                let cpp_name = if is_identifier(local_name(name)) {
+                    identifier(local_name(name))
+                } else {
+                    identifier(&format!("{context}_t"))
+                };
+                self.nominal(&cpp_name, ty)?;
+                cpp_name
+            }
+        })
+    }
+
+    /// Emits a class or enum definition under `cpp_name`, ignoring any
+    /// dimensions of `ty`.
+    fn nominal(&mut self, cpp_name: &str, ty: &Type) -> Result<(), Box<dyn Error>> {
+        if !self.nominals.define(cpp_name, ty) {
+            return Ok(());
+        }
+
+        let mut definition = String::new();
+        let (_, width) = packed_elements(ty)?;
+        match ty {
+            Type::Struct { fields, .. } | Type::Union { fields, .. } => {
+                let offsets = ty.field_offsets()?;
+                let mut accessors = String::new();
+                for (field, offset) in fields.iter().zip(offsets) {
+                    let context = format!("{cpp_name}_{}", field.name);
+                    self.accessors(&mut accessors, &field.name, &field.ty, offset, &context)?;
+                }
+                writeln!(definition, "class {cpp_name} {{")?;
+                writeln!(definition, " public:")?;
+                writeln!(definition, "  static constexpr unsigned kWidth = {width};")?;
+                writeln!(
+                    definition,
+                    "  static constexpr unsigned kWords = {};",
+                    width.div_ceil(64).max(1)
+                )?;
+                writeln!(definition)?;
+                write!(definition, "{accessors}")?;
+                writeln!(definition, "  uint64_t words[kWords] = {{}};")?;
+                writeln!(definition, "}};")?;
+            }
+            Type::Enum { variants, .. } => {
+                if width > 64 {
+                    Err(format!(
+                        "Enum `{cpp_name}` is wider than 64 bits and cannot be represented in C++"
+                    ))?;
+                }
+                let signed = enum_signed(ty);
+                writeln!(
+                    definition,
+                    "enum class {cpp_name} : {} {{",
+                    integer_type(width, signed)
+                )?;
+                for variant in variants {
+                    writeln!(
+                        definition,
+                        "  {} = {},",
+                        identifier(&variant.name),
+                        integer_literal(&variant.value, width, signed)
+                    )?;
+                }
+                writeln!(definition, "}};")?;
+            }
+            Type::Logic { .. } => unreachable!(),
+        }
+        self.items.push(definition);
+        Ok(())
+    }
+
+    /// Appends the getter and setter for one field located `offset` bits into
+    /// its enclosing struct or union.
+    fn accessors(
+        &mut self,
+        output: &mut String,
+        field_name: &str,
+        ty: &Type,
+        offset: usize,
+        context: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        if !ty.unpacked_dimensions().is_empty() {
+            Err(format!(
+                "Field `{field_name}` has unpacked dimensions, which have no packed layout"
+            ))?;
+        }
+        let (dimensions, width) = packed_elements(ty)?;
+        let name = identifier(field_name);
+        let parameters = dimensions
+            .iter()
+            .enumerate()
+            .map(|(index, _)| format!("int i{index}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let lsb = lsb_expression(offset, dimensions, width);
+        let setter_parameters = if parameters.is_empty() {
+            String::new()
+        } else {
+            format!("{parameters}, ")
+        };
+
+        let (value_type, get, set) = match ty {
+            Type::Logic { signed, .. } if width <= 64 => {
+                let value_type = integer_type(width, *signed);
+                let bits = format!("detail::get_bits(words, {lsb}, {width})");
+                let get = if *signed {
+                    format!(
+                        "return static_cast<{value_type}>(detail::sign_extend({bits}, {width}));"
+                    )
+                } else {
+                    format!("return static_cast<{value_type}>({bits});")
+                };
+                let set = format!(
+                    "detail::set_bits(words, {lsb}, {width}, static_cast<uint64_t>(value));"
+                );
+                (value_type, get, set)
+            }
+            Type::Logic { .. } => {
+                let value_type = storage_type(width, false);
+                let get = format!(
+                    "{value_type} value{{}};\n    \
+                     detail::copy_bits(value.data(), 0, words, {lsb}, {width});\n    \
+                     return value;"
+                );
+                let set = format!("detail::copy_bits(words, {lsb}, value.data(), 0, {width});");
+                (value_type, get, set)
+            }
+            Type::Enum { .. } => {
+                let value_type = self.element(ty, context)?;
+                let bits = format!("detail::get_bits(words, {lsb}, {width})");
+                let bits = if enum_signed(ty) {
+                    format!("detail::sign_extend({bits}, {width})")
+                } else {
+                    bits
+                };
+                let get = format!("return static_cast<{value_type}>({bits});");
+                let set = format!(
+                    "detail::set_bits(words, {lsb}, {width}, static_cast<uint64_t>(value));"
+                );
+                (value_type, get, set)
+            }
+            Type::Struct { .. } | Type::Union { .. } => {
+                let value_type = self.element(ty, context)?;
+                let get = format!(
+                    "{value_type} value;\n    \
+                     detail::copy_bits(value.words, 0, words, {lsb}, {width});\n    \
+                     return value;"
+                );
+                let set = format!("detail::copy_bits(words, {lsb}, value.words, 0, {width});");
+                (value_type, get, set)
+            }
+        };
+
+        writeln!(output, "  {value_type} {name}({parameters}) const {{")?;
+        writeln!(output, "    {get}")?;
+        writeln!(output, "  }}")?;
+        writeln!(
+            output,
+            "  void set_{name}({setter_parameters}{value_type} value) {{"
+        )?;
+        writeln!(output, "    {set}")?;
+        writeln!(output, "  }}")?;
+        writeln!(output)?;
+        Ok(())
+    }
+}
+
+/// Builds the expression for the least significant bit of the element at
+/// SystemVerilog indices `i0, i1, ...` within `dimensions`, starting `offset`
+/// bits into the enclosing type.
+///
+/// The rightmost index of each range is stored at the lowest position, so an
+/// ascending range such as `[0:3]` places element 0 in the most significant
+/// bits, as in SystemVerilog.
+fn lsb_expression(offset: usize, dimensions: &[Range], width: usize) -> String {
+    let mut terms = Vec::new();
+    if offset != 0 || dimensions.is_empty() {
+        terms.push(offset.to_string());
+    }
+    let mut stride = width;
+    for (index, range) in dimensions.iter().enumerate().rev() {
+        let position = match (range.msb >= range.lsb, range.lsb) {
+            (true, 0) => format!("static_cast<unsigned>(i{index})"),
+            (true, lsb) => format!("static_cast<unsigned>(i{index} - {lsb})"),
+            (false, lsb) => format!("static_cast<unsigned>({lsb} - i{index})"),
+        };
+        if stride == 1 {
+            terms.push(position);
+        } else {
+            terms.push(format!("{position} * {stride}"));
+        }
+        stride *= range_length(range);
+    }
+    terms.join(" + ")
+}
+
+/// Whether an enum's underlying type is signed.
+fn enum_signed(ty: &Type) -> bool {
+    match ty {
+        Type::Enum { variants, .. } => variants.iter().any(|v| v.signed || v.value < BigInt::ZERO),
+        _ => false,
+    }
+}
+
+/// Wraps `element` in one `std::array` per dimension, outermost first.
+fn nested_arrays(element: String, dimensions: &[Range]) -> String {
+    dimensions.iter().rev().fold(element, |element, range| {
+        format!("std::array<{element}, {}>", range_length(range))
+    })
+}
+
+/// The integer type holding a bit vector, or a word array if it is wider than
+/// 64 bits.
+fn storage_type(width: usize, signed: bool) -> String {
+    if width <= 64 {
+        integer_type(width, signed)
+    } else {
+        format!("std::array<uint64_t, {}>", width.div_ceil(64))
+    }
+}
+
+/// The smallest fixed-width integer type holding `width` bits.
+fn integer_type(width: usize, signed: bool) -> String {
+    let bits = match width {
+        0..=8 => 8,
+        9..=16 => 16,
+        17..=32 => 32,
+        _ => 64,
+    };
+    if signed {
+        format!("int{bits}_t")
+    } else {
+        format!("uint{bits}_t")
+    }
+}
+
+/// Renders an integer constant so that it is valid for the type chosen by
+/// [`integer_type`].
+fn integer_literal(value: &BigInt, width: usize, signed: bool) -> String {
+    let suffix = match (width > 32, signed) {
+        (false, false) => "u",
+        (false, true) => "",
+        (true, false) => "ull",
+        (true, true) => "ll",
+    };
+    if signed && width > 32 && *value == BigInt::from(i64::MIN) {
+        // The magnitude of the most negative value is not a valid literal.
+        return format!("(-{}ll - 1)", i64::MAX);
+    }
+    format!("{value}{suffix}")
+}
+
+/// Maps a SystemVerilog identifier to a valid C++ identifier.
+fn identifier(name: &str) -> String {
+    crate::codegen::identifier(name, CPP_KEYWORDS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::fixtures::package;
+
+    /// Returns the generated header without the fixed preamble and helpers.
+    fn body(header: &str) -> &str {
+        let start = header.find("}  // namespace detail\n").unwrap();
+        &header[start + "}  // namespace detail\n".len()..]
+    }
+
+    #[test]
+    fn test_constants_and_enums() {
+        let package = package(
+            "pkg",
+            &[
+                ("WIDTH", "22"),
+                ("MASK", "40'hff"),
+                ("NEG", "-16'sd3"),
+                ("state_t", "enum{IDLE=2'd0,BUSY=2'd1}state_t"),
+                ("addr_t", "logic[39:0]"),
+                ("wide_t", "logic[99:0]$[0:1]"),
+            ],
+        );
+
+        let header = generate_cpp_header(&package, false).unwrap();
+        assert!(header.starts_with(
+            "// Generated from SystemVerilog package `pkg`.
+
+#pragma once
+
+#include <array>
+#include <cstdint>
+
+namespace pkg {
+"
+        ));
+        assert_eq!(
+            body(&header),
+            "
+constexpr uint64_t MASK = 255ull;
+constexpr int16_t NEG = -3;
+constexpr int32_t WIDTH = 22;
+
+using addr_t = uint64_t;
+
+enum class state_t : uint8_t {
+  IDLE = 0u,
+  BUSY = 1u,
+};
+
+using wide_t = std::array<std::array<uint64_t, 2>, 2>;
+
+}  // namespace pkg
+"
+        );
+    }
+
+    #[test]
+    fn test_struct_accessors() {
+        let package = package(
+            "pkg",
+            &[
+                (
+                    "pkt_t",
+                    "struct packed{logic[7:0] data;logic signed[3:0] delta;\
+                     struct packed{logic a;}pkg::flag_t[0:1] flags;logic[1:0][69:0] wide;}pkt_t",
+                ),
+                ("flag_t", "struct packed{logic a;}flag_t"),
+            ],
+        );
+
+        assert_eq!(
+            body(&generate_cpp_header(&package, false).unwrap()),
+            "
+class flag_t {
+ public:
+  static constexpr unsigned kWidth = 1;
+  static constexpr unsigned kWords = 1;
+
+  uint8_t a() const {
+    return static_cast<uint8_t>(detail::get_bits(words, 0, 1));
+  }
+  void set_a(uint8_t value) {
+    detail::set_bits(words, 0, 1, static_cast<uint64_t>(value));
+  }
+
+  uint64_t words[kWords] = {};
+};
+
+class pkt_t {
+ public:
+  static constexpr unsigned kWidth = 154;
+  static constexpr unsigned kWords = 3;
+
+  uint8_t data() const {
+    return static_cast<uint8_t>(detail::get_bits(words, 146, 8));
+  }
+  void set_data(uint8_t value) {
+    detail::set_bits(words, 146, 8, static_cast<uint64_t>(value));
+  }
+
+  int8_t delta() const {
+    return static_cast<int8_t>(detail::sign_extend(detail::get_bits(words, 142, 4), 4));
+  }
+  void set_delta(int8_t value) {
+    detail::set_bits(words, 142, 4, static_cast<uint64_t>(value));
+  }
+
+  flag_t flags(int i0) const {
+    flag_t value;
+    detail::copy_bits(value.words, 0, words, 140 + static_cast<unsigned>(1 - i0), 1);
+    return value;
+  }
+  void set_flags(int i0, flag_t value) {
+    detail::copy_bits(words, 140 + static_cast<unsigned>(1 - i0), value.words, 0, 1);
+  }
+
+  std::array<uint64_t, 2> wide(int i0) const {
+    std::array<uint64_t, 2> value{};
+    detail::copy_bits(value.data(), 0, words, static_cast<unsigned>(i0) * 70, 70);
+    return value;
+  }
+  void set_wide(int i0, std::array<uint64_t, 2> value) {
+    detail::copy_bits(words, static_cast<unsigned>(i0) * 70, value.data(), 0, 70);
+  }
+
+  uint64_t words[kWords] = {};
+};
+
+}  // namespace pkg
+"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_values() {
+        let package = package("pkg", &[("NAME", "\"core\""), ("HUGE", "65'h1")]);
+
+        assert!(generate_cpp_header(&package, false).is_err());
+        assert_eq!(
+            body(&generate_cpp_header(&package, true).unwrap()),
+            "\n}  // namespace pkg\n"
+        );
+    }
+}
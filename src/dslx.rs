@@ -0,0 +1,370 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generation of DSLX definitions from extracted SystemVerilog packages.
+//!
+//! Package `localparam`s become `const`s, packed struct typedefs become
+//! `struct`s, enum typedefs become `enum`s and every other typedef becomes a
+//! `type` alias. Packed and unpacked dimensions both map to DSLX arrays, with
+//! the outermost SystemVerilog dimension as the outermost DSLX array. Element
+//! ordering within a dimension (`[7:0]` vs. `[0:7]`) has no DSLX equivalent
+//! and is not preserved.
+
+use crate::Package;
+use crate::codegen::{
+    Nominal, NominalTypes, PackageItems, is_identifier, is_nominal_definition, local_name,
+    package_name, packed_elements, range_length,
+};
+use crate::extract::Type;
+use num_bigint::BigInt;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::Write;
+
+/// DSLX keywords that cannot be used as identifiers.
+const DSLX_KEYWORDS: &[&str] = &[
+    "as", "const", "else", "enum", "fn", "for", "if", "impl", "import", "in", "let", "match",
+    "proc", "pub", "self", "Self", "struct", "type", "use",
+];
+
+/// Renders the parameters and typedefs of `package` as a DSLX module.
+///
+/// Definitions are emitted in a deterministic order: constants sorted by name,
+/// followed by types sorted by name, with any struct or enum a type depends on
+/// emitted before its first use. Types qualified with another package name
+/// (`other_pkg::T`) are referenced through an `import` rather than redefined.
+///
+/// Parameters whose values cannot be represented in DSLX, such as strings and
+/// reals, produce an error unless `skip_unsupported` is set, in which case
+/// they are omitted.
+pub fn generate_dslx(package: &Package, skip_unsupported: bool) -> Result<String, Box<dyn Error>> {
+    let items = PackageItems::new(package, "DSLX", skip_unsupported)?;
+
+    let mut consts = String::new();
+    for constant in items.constants.iter() {
+        writeln!(
+            consts,
+            "pub const {} = {}:{};",
+            identifier(constant.name),
+            bits_type(constant.width, constant.signed),
+            constant.value
+        )?;
+    }
+
+    let mut emitter = DslxEmitter::new(&package.name);
+    emitter.nominals.claim_all(&items.typedefs, identifier);
+    for (name, ty) in items.typedefs.iter() {
+        emitter.typedef(name, ty)?;
+    }
+
+    let mut sections = Vec::new();
+    if !emitter.imports.is_empty() {
+        sections.push(
+            emitter
+                .imports
+                .iter()
+                .map(|import| format!("import {import};\n"))
+                .collect(),
+        );
+    }
+    if !consts.is_empty() {
+        sections.push(consts);
+    }
+    sections.extend(emitter.items);
+
+    let mut output = format!(
+        "// Generated from SystemVerilog package `{}`.\n",
+        package.name
+    );
+    for section in sections {
+        output.push('\n');
+        output.push_str(&section);
+    }
+    Ok(output)
+}
+
+/// Returns the DSLX spelling of `ty`, referring to structs and enums by name.
+///
+/// This is intended for types whose nominal definitions are emitted
+/// separately, for example with [`generate_dslx`].
+pub fn dslx_type(ty: &Type) -> String {
+    let element = match ty {
+        Type::Logic { signed, .. } => {
+            let width = ty.packed_dimensions().last().map(range_length).unwrap_or(1);
+            bits_type(width, *signed)
+        }
+        Type::Struct { name, .. } | Type::Union { name, .. } | Type::Enum { name, .. } => {
+            reference(name)
+        }
+    };
+    format!("{element}{}", array_suffix(ty))
+}
+
+/// Tracks the definitions emitted so far for one package.
+struct DslxEmitter<'a> {
+    package: &'a str,
+    nominals: NominalTypes,
+    imports: BTreeSet<String>,
+    /// Rendered top-level definitions in dependency order.
+    items: Vec<String>,
+}
+
+impl<'a> DslxEmitter<'a> {
+    fn new(package: &'a str) -> Self {
+        Self {
+            package,
+            nominals: NominalTypes::default(),
+            imports: BTreeSet::new(),
+            items: Vec::new(),
+        }
+    }
+
+    /// Emits a package-level typedef named `name`.
+    fn typedef(&mut self, name: &str, ty: &Type) -> Result<(), Box<dyn Error>> {
+        if is_nominal_definition(name, ty) {
+            return self.nominal(&identifier(name), ty);
+        }
+        let rendered = self.use_site(ty, name)?;
+        self.items
+            .push(format!("pub type {} = {rendered};\n", identifier(name)));
+        Ok(())
+    }
+
+    /// Renders `ty` where it is used, first emitting any nominal definition it
+    /// requires. `context` names anonymous aggregates that have no usable name.
+    fn use_site(&mut self, ty: &Type, context: &str) -> Result<String, Box<dyn Error>> {
+        let element = match ty {
+            Type::Logic { .. } => return Ok(dslx_type(ty)),
+            Type::Struct { name, .. } | Type::Union { name, .. } | Type::Enum { name, .. } => {
+                match (package_name(name), self.nominals.lookup(ty)) {
+                    (Some(package), _) if package != self.package => {
+                        self.imports.insert(identifier(package));
+                        reference(name)
+                    }
+                    (_, Some(Nominal::Defined(existing))) => existing,
+                    (_, Some(Nominal::Claimed(existing, claimed))) => {
+                        self.nominal(&existing, &claimed)?;
+                        existing
+                    }
+                    (_, None) => {
+                        let dslx_name = if is_identifier(local_name(name)) {
+                            identifier(local_name(name))
+                        } else {
+                            identifier(&format!("{context}_t"))
+                        };
+                        self.nominal(&dslx_name, ty)?;
+                        dslx_name
+                    }
+                }
+            }
+        };
+        Ok(format!("{element}{}", array_suffix(ty)))
+    }
+
+    /// Emits a struct, union or enum definition under `dslx_name`, ignoring
+    /// any dimensions of `ty`.
+    fn nominal(&mut self, dslx_name: &str, ty: &Type) -> Result<(), Box<dyn Error>> {
+        if !self.nominals.define(dslx_name, ty) {
+            return Ok(());
+        }
+
+        let mut definition = String::new();
+        match ty {
+            Type::Struct { fields, .. } => {
+                writeln!(definition, "pub struct {dslx_name} {{")?;
+                for field in fields {
+                    let context = format!("{dslx_name}_{}", field.name);
+                    let field_ty = self.use_site(&field.ty, &context)?;
+                    writeln!(definition, "    {}: {field_ty},", identifier(&field.name))?;
+                }
+                writeln!(definition, "}}")?;
+            }
+            Type::Enum { variants, .. } => {
+                let (_, width) = packed_elements(ty)?;
+                let signed = variants.iter().any(|v| v.signed || v.value < BigInt::ZERO);
+                writeln!(
+                    definition,
+                    "pub enum {dslx_name} : {} {{",
+                    bits_type(width, signed)
+                )?;
+                for variant in variants {
+                    writeln!(
+                        definition,
+                        "    {} = {},",
+                        identifier(&variant.name),
+                        variant.value
+                    )?;
+                }
+                writeln!(definition, "}}")?;
+            }
+            Type::Union { .. } => {
+                // DSLX has no unions; expose the union as its raw bits.
+                let (_, width) = packed_elements(ty)?;
+                writeln!(
+                    definition,
+                    "pub type {dslx_name} = {};",
+                    bits_type(width, false)
+                )?;
+            }
+            Type::Logic { .. } => unreachable!(),
+        }
+        self.items.push(definition);
+        Ok(())
+    }
+}
+
+/// Renders the array dimensions of `ty`, innermost first as DSLX expects.
+///
+/// For bit vectors the innermost packed dimension is the element width rather
+/// than an array dimension.
+fn array_suffix(ty: &Type) -> String {
+    let packed = match ty {
+        Type::Logic { .. } => {
+            let packed = ty.packed_dimensions();
+            &packed[..packed.len().saturating_sub(1)]
+        }
+        _ => &ty.packed_dimensions()[..],
+    };
+    ty.unpacked_dimensions()
+        .iter()
+        .chain(packed.iter())
+        .rev()
+        .map(|range| format!("[{}]", range_length(range)))
+        .collect()
+}
+
+fn bits_type(width: usize, signed: bool) -> String {
+    match (width, signed) {
+        (1..=64, false) => format!("u{width}"),
+        (1..=64, true) => format!("s{width}"),
+        (_, false) => format!("uN[{width}]"),
+        (_, true) => format!("sN[{width}]"),
+    }
+}
+
+/// Refers to a nominal type, keeping any package qualification.
+fn reference(name: &str) -> String {
+    match package_name(name) {
+        Some(package) => format!("{}::{}", identifier(package), identifier(local_name(name))),
+        None => identifier(local_name(name)),
+    }
+}
+
+/// Maps a SystemVerilog identifier to a valid DSLX identifier.
+fn identifier(name: &str) -> String {
+    crate::codegen::identifier(name, DSLX_KEYWORDS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::fixtures::package;
+
+    #[test]
+    fn test_constants_and_aliases() {
+        let package = package(
+            "pkg",
+            &[
+                ("WIDTH", "22"),
+                ("MASK", "8'hff"),
+                ("NEG", "-16'sd3"),
+                ("addr_t", "logic[39:0]"),
+                ("matrix_t", "logic signed[3:0][7:0]$[0:1]"),
+                ("wide_t", "logic[99:0]"),
+            ],
+        );
+
+        assert_eq!(
+            generate_dslx(&package, false).unwrap(),
+            "// Generated from SystemVerilog package `pkg`.
+
+pub const MASK = u8:255;
+pub const NEG = s16:-3;
+pub const WIDTH = s32:22;
+
+pub type addr_t = u40;
+
+pub type matrix_t = s8[4][2];
+
+pub type wide_t = uN[100];
+"
+        );
+    }
+
+    #[test]
+    fn test_structs_and_enums() {
+        let package = package(
+            "pkg",
+            &[
+                (
+                    "inner_t",
+                    "struct packed{logic[7:0] data;logic valid;}inner_t",
+                ),
+                (
+                    "outer_t",
+                    "struct packed{struct packed{logic[7:0] data;logic valid;}inner_t[1:0] pair;\
+                     enum{IDLE=2'd0,BUSY=2'd1}pkg::state_t state;}outer_t",
+                ),
+                (
+                    "inner_array_t",
+                    "struct packed{logic[7:0] data;logic valid;}inner_array_t[3:0]",
+                ),
+                ("offset_t", "enum{ZERO=32'sd0,NEG=-32'sd1}offset_t"),
+            ],
+        );
+
+        assert_eq!(
+            generate_dslx(&package, false).unwrap(),
+            "// Generated from SystemVerilog package `pkg`.
+
+pub struct inner_t {
+    data: u8,
+    valid: u1,
+}
+
+pub type inner_array_t = inner_t[4];
+
+pub enum offset_t : s32 {
+    ZERO = 0,
+    NEG = -1,
+}
+
+pub enum state_t : u2 {
+    IDLE = 0,
+    BUSY = 1,
+}
+
+pub struct outer_t {
+    pair: inner_t[2],
+    state: state_t,
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_foreign_types_and_unsupported_values() {
+        let package = package(
+            "pkg",
+            &[
+                (
+                    "msg_t",
+                    "struct packed{struct packed{logic a;}base_pkg::hdr_t hdr;}msg_t",
+                ),
+                ("NAME", "\"core\""),
+            ],
+        );
+
+        assert!(generate_dslx(&package, false).is_err());
+        assert_eq!(
+            generate_dslx(&package, true).unwrap(),
+            "// Generated from SystemVerilog package `pkg`.
+
+import base_pkg;
+
+pub struct msg_t {
+    hdr: base_pkg::hdr_t,
+}
+"
+        );
+    }
+}
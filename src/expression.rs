@@ -0,0 +1,239 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rendering of Slang expression nodes back into SystemVerilog text.
+//!
+//! Only the expression forms that commonly appear in port expressions, port
+//! connections and parameter values are rendered structurally. Anything else
+//! falls back to the constant value Slang computed for it, if there is one.
+
+use serde_json::Value;
+
+/// Renders an expression node as SystemVerilog text, or returns `None` if the
+/// node is neither a supported expression nor a constant.
+pub(crate) fn render_expression(expr: &Value) -> Option<String> {
+    let rendered = match expr["kind"].as_str()? {
+        "IntegerLiteral" | "RealLiteral" | "TimeLiteral" | "UnbasedUnsizedIntegerLiteral" => {
+            expr["value"].as_str().map(str::to_string)
+        }
+        "StringLiteral" => expr["literal"].as_str().map(str::to_string),
+        "NamedValue" | "HierarchicalValue" => link_name(&expr["symbol"]).map(str::to_string),
+        "Conversion" => render_expression(&expr["operand"]),
+        "Concatenation" => {
+            let operands = expr["operands"]
+                .as_array()?
+                .iter()
+                .map(render_expression)
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("{{{}}}", operands.join(", ")))
+        }
+        "Replication" => Some(format!(
+            "{{{}{}}}",
+            render_operand(&expr["count"])?,
+            render_expression(&expr["concat"])?
+        )),
+        "ElementSelect" => Some(format!(
+            "{}[{}]",
+            render_operand(&expr["value"])?,
+            render_expression(&expr["selector"])?
+        )),
+        "RangeSelect" => {
+            let separator = match expr["selectionKind"].as_str() {
+                Some("IndexedUp") => "+:",
+                Some("IndexedDown") => "-:",
+                _ => ":",
+            };
+            Some(format!(
+                "{}[{}{separator}{}]",
+                render_operand(&expr["value"])?,
+                render_expression(&expr["left"])?,
+                render_expression(&expr["right"])?
+            ))
+        }
+        "MemberAccess" => Some(format!(
+            "{}.{}",
+            render_operand(&expr["value"])?,
+            link_name(&expr["member"])?
+        )),
+        "UnaryOp" => Some(format!(
+            "{}{}",
+            unary_operator(expr["op"].as_str()?)?,
+            render_operand(&expr["operand"])?
+        )),
+        "BinaryOp" => Some(format!(
+            "{} {} {}",
+            render_operand(&expr["left"])?,
+            binary_operator(expr["op"].as_str()?)?,
+            render_operand(&expr["right"])?
+        )),
+        "ConditionalOp" => Some(format!(
+            "{} ? {} : {}",
+            render_operand(&expr["conditions"][0]["expr"])?,
+            render_operand(&expr["left"])?,
+            render_operand(&expr["right"])?
+        )),
+        _ => None,
+    };
+    rendered.or_else(|| expr["constant"].as_str().map(str::to_string))
+}
+
+/// Returns the symbol name from a link, which is either `"<address> <name>"`
+/// or, without addresses, just `"<name>"`. Inline symbol objects from older
+/// Slang releases are accepted too.
+pub(crate) fn link_name(link: &Value) -> Option<&str> {
+    match link {
+        Value::String(text) => match text.split_once(' ') {
+            Some((address, name)) if address.parse::<u64>().is_ok() => Some(name),
+            _ => Some(text),
+        },
+        Value::Object(_) => link["name"].as_str(),
+        _ => None,
+    }
+}
+
+/// The address of the symbol a link refers to, if Slang wrote one.
+pub(crate) fn link_address(link: &Value) -> Option<u64> {
+    match link {
+        Value::String(text) => text.split_once(' ')?.0.parse().ok(),
+        Value::Object(_) => link["addr"].as_u64(),
+        _ => None,
+    }
+}
+
+/// Renders an operand, parenthesizing operators so that the result does not
+/// depend on precedence.
+fn render_operand(expr: &Value) -> Option<String> {
+    let rendered = render_expression(expr)?;
+    match expr["kind"].as_str() {
+        Some("BinaryOp" | "ConditionalOp") => Some(format!("({rendered})")),
+        _ => Some(rendered),
+    }
+}
+
+fn unary_operator(op: &str) -> Option<&'static str> {
+    Some(match op {
+        "Plus" => "+",
+        "Minus" => "-",
+        "BitwiseNot" => "~",
+        "BitwiseAnd" => "&",
+        "BitwiseOr" => "|",
+        "BitwiseXor" => "^",
+        "BitwiseNand" => "~&",
+        "BitwiseNor" => "~|",
+        "BitwiseXnor" => "~^",
+        "LogicalNot" => "!",
+        _ => return None,
+    })
+}
+
+fn binary_operator(op: &str) -> Option<&'static str> {
+    Some(match op {
+        "Add" => "+",
+        "Subtract" => "-",
+        "Multiply" => "*",
+        "Divide" => "/",
+        "Mod" => "%",
+        "BinaryAnd" => "&",
+        "BinaryOr" => "|",
+        "BinaryXor" => "^",
+        "BinaryXnor" => "~^",
+        "Equality" => "==",
+        "Inequality" => "!=",
+        "CaseEquality" => "===",
+        "CaseInequality" => "!==",
+        "GreaterThanEqual" => ">=",
+        "GreaterThan" => ">",
+        "LessThanEqual" => "<=",
+        "LessThan" => "<",
+        "WildcardEquality" => "==?",
+        "WildcardInequality" => "!=?",
+        "LogicalAnd" => "&&",
+        "LogicalOr" => "||",
+        "LogicalImplication" => "->",
+        "LogicalEquivalence" => "<->",
+        "LogicalShiftLeft" => "<<",
+        "LogicalShiftRight" => ">>",
+        "ArithmeticShiftLeft" => "<<<",
+        "ArithmeticShiftRight" => ">>>",
+        "Power" => "**",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn named(name: &str) -> Value {
+        json!({ "kind": "NamedValue", "symbol": format!("100 {name}") })
+    }
+
+    fn literal(value: &str) -> Value {
+        json!({ "kind": "IntegerLiteral", "value": value, "constant": value })
+    }
+
+    #[test]
+    fn renders_selects_and_concatenations() {
+        let expr = json!({
+            "kind": "Concatenation",
+            "operands": [
+                {
+                    "kind": "RangeSelect",
+                    "selectionKind": "IndexedUp",
+                    "value": {
+                        "kind": "MemberAccess",
+                        "value": named("bus"),
+                        "member": "200 data"
+                    },
+                    "left": literal("4"),
+                    "right": literal("2")
+                },
+                { "kind": "ElementSelect", "value": named("b"), "selector": literal("0") },
+                {
+                    "kind": "Replication",
+                    "count": literal("2"),
+                    "concat": { "kind": "Concatenation", "operands": [literal("1'b0")] }
+                }
+            ]
+        });
+        assert_eq!(
+            render_expression(&expr).unwrap(),
+            "{bus.data[4+:2], b[0], {2{1'b0}}}"
+        );
+    }
+
+    #[test]
+    fn renders_operators_and_constants() {
+        let expr = json!({
+            "kind": "BinaryOp",
+            "op": "Multiply",
+            "left": {
+                "kind": "BinaryOp",
+                "op": "Subtract",
+                "left": named("WIDTH"),
+                "right": literal("1")
+            },
+            "right": {
+                "kind": "UnaryOp",
+                "op": "BitwiseNot",
+                "operand": { "kind": "Conversion", "operand": named("mask") }
+            }
+        });
+        assert_eq!(render_expression(&expr).unwrap(), "(WIDTH - 1) * ~mask");
+
+        let call = json!({ "kind": "Call", "constant": "8'h2a" });
+        assert_eq!(render_expression(&call).unwrap(), "8'h2a");
+        assert!(render_expression(&json!({ "kind": "Call" })).is_none());
+    }
+
+    #[test]
+    fn reads_link_names() {
+        assert_eq!(link_name(&json!("1234 wire")), Some("wire"));
+        assert_eq!(link_name(&json!("wire")), Some("wire"));
+        assert_eq!(
+            link_name(&json!({ "name": "tri", "kind": "NetType" })),
+            Some("tri")
+        );
+        assert_eq!(link_name(&json!(null)), None);
+    }
+}
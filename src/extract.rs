@@ -21,7 +21,13 @@
 //! representation. [`TypeResolver`] bridges the formats by indexing addressable
 //! JSON nodes and expanding v10+ links back into that representation before
 //! parsing. Older inline types contain no links and pass through unchanged.
+//! Where a link refers to a structured type node, [`type_nodes`] builds the
+//! `Type` from that node directly instead.
 
+use crate::attributes::{Attribute, extract_attributes};
+use crate::const_value::{ConstValue, ConstValueError};
+use crate::expression::{link_name, render_expression};
+use crate::location::{SourceLocation, source_location};
 use num_bigint::BigInt;
 use num_traits::Num;
 use regex::Regex;
@@ -31,26 +37,113 @@ use std::error::Error;
 use std::hash::Hash;
 use std::str::FromStr;
 
+mod type_compat;
+pub(crate) use type_compat::bit_stream_width;
+pub use type_compat::{TypeMismatch, TypeRelation};
+
 mod type_extract;
+mod type_nodes;
+pub(crate) use type_extract::parse_resolved_type;
 pub use type_extract::{Field, Range, Type, Variant, parse_type_definition};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum PortDir {
     Input,
     Output,
     InOut,
+    Ref,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Port {
     pub dir: PortDir,
     pub name: String,
     pub ty: Type,
+    /// Whether the port is a net or a variable, if Slang reports the symbol
+    /// it connects to. Ports made of several signals (`.a({b, c})`) have none.
+    pub kind: Option<PortKind>,
+    /// The default value of an input port (`input logic en = 1'b1`).
+    pub default: Option<String>,
+    /// The port expression of an explicit or non-ANSI port (`.a({b, c})`),
+    /// unless the port simply refers to a signal of the same name.
+    pub expression: Option<String>,
+    pub location: Option<SourceLocation>,
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PortKind {
+    /// A net port, with its net type such as `wire`, `tri`, `wand` or a
+    /// user-defined nettype.
+    Net {
+        net_type: String,
+    },
+    Variable,
 }
 
+#[derive(Debug, PartialEq, Clone)]
 pub struct ParameterDef {
     pub name: String,
-    pub ty: Type,
+    /// The parameter's type, or for a type parameter the elaborated type it
+    /// stands for. `None` for `real`, `shortreal`, `realtime` and `string`,
+    /// which have no [`Type`].
+    ///
+    /// The declared default of a type parameter is not reported: Slang's JSON
+    /// output only carries the elaborated type, not the default written in the
+    /// declaration. The default is only known to equal `ty` when `source` is
+    /// [`ParameterSource::Default`].
+    pub ty: Option<Type>,
+    /// The elaborated value as printed by Slang, if it has one.
+    pub value: Option<String>,
+    pub kind: ParameterKind,
+    /// Whether the parameter is a `localparam`, or a `parameter` that the
+    /// standard treats as local because the module has a parameter port list.
+    pub is_local: bool,
+    /// Whether the parameter is declared in the parameter port list
+    /// (`module m #(parameter N = 1)`) rather than in the module body.
+    pub is_port: bool,
+    pub source: ParameterSource,
+    pub location: Option<SourceLocation>,
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParameterKind {
+    /// A value parameter (`parameter int N = 4`).
+    Value,
+    /// A type parameter (`parameter type T = logic [7:0]`). Its elaborated
+    /// type is the parameter's `ty`.
+    Type,
+}
+
+impl ParameterDef {
+    /// Parses the elaborated value, naming the fields of unpacked struct
+    /// values after the parameter's type. Type parameters have no value.
+    pub fn const_value(&self) -> Result<ConstValue, ConstValueError> {
+        let value = self
+            .value
+            .as_ref()
+            .ok_or_else(|| ConstValueError::new(format!("`{}` has no value", self.name)))?;
+        let value = value.parse::<ConstValue>()?;
+        Ok(match &self.ty {
+            Some(ty) => value.with_type(ty),
+            None => value,
+        })
+    }
+}
+
+/// Where the value of a parameter comes from.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ParameterSource {
+    /// The default in the declaration.
+    Default,
+    /// A parameter value assignment on the instance (`fifo #(.W(8)) u_fifo`),
+    /// as reported by Slang's `isOverridden` flag.
+    Instance,
+    /// A `-G` override from `SlangConfig::parameters`. These are only known to
+    /// the extractors that take a `SlangConfig`, and only apply to top-level
+    /// modules.
+    CommandLine,
 }
 
 impl FromStr for PortDir {
@@ -61,6 +154,7 @@ impl FromStr for PortDir {
             "In" => Ok(PortDir::Input),
             "Out" => Ok(PortDir::Output),
             "InOut" => Ok(PortDir::InOut),
+            "Ref" => Ok(PortDir::Ref),
             _ => Err(format!("Unsupported I/O direction: {}", s)),
         }
     }
@@ -74,14 +168,14 @@ pub fn extract_ports(
     extract_ports_from_value(&result, skip_unsupported)
 }
 
-struct MemberIter<'a> {
+pub(crate) struct MemberIter<'a> {
     members: Option<&'a Vec<Value>>,
     kinds: &'a [&'a str],
     current: usize,
 }
 
 impl<'a> MemberIter<'a> {
-    fn new(value: &'a Value, kinds: &'a [&'a str]) -> Self {
+    pub(crate) fn new(value: &'a Value, kinds: &'a [&'a str]) -> Self {
         Self {
             members: value["members"].as_array(),
             kinds,
@@ -114,7 +208,7 @@ fn parse_type_definition_no_error(type_str: &str) -> Result<Type, Box<dyn Error>
     if type_str == "<error>" {
         Err("Found \"<error>\" type in Slang JSON output.")?;
     }
-    parse_type_definition(type_str)
+    parse_resolved_type(type_str)
 }
 
 fn insert_to_vacant<K, V>(map: &mut HashMap<K, V>, key: K, value: V) -> Result<(), String>
@@ -135,6 +229,15 @@ pub fn extract_ports_from_value(
     value: &Value,
     skip_unsupported: bool,
 ) -> HashMap<String, Vec<Port>> {
+    try_extract_ports_from_value(value, skip_unsupported).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Extracts the ports of each top-level module like
+/// [`extract_ports_from_value`], but returns the error it would panic with.
+pub(crate) fn try_extract_ports_from_value(
+    value: &Value,
+    skip_unsupported: bool,
+) -> Result<HashMap<String, Vec<Port>>, String> {
     let mut ports_map = HashMap::new();
     let type_resolver = TypeResolver::new(value);
 
@@ -143,48 +246,130 @@ pub fn extract_ports_from_value(
         if module_name.is_empty() {
             continue;
         }
-        let mut ports = Vec::new();
         let body = type_resolver.resolve_node(&member["body"]);
-        for instance_member in MemberIter::new(body, &["Port", "InterfacePort"]) {
-            let kind = instance_member["kind"].as_str().unwrap();
-            match kind {
-                "Port" => {
-                    let port_name = instance_member["name"].as_str().unwrap();
-                    let direction = instance_member["direction"].as_str().unwrap();
-                    let type_str = type_resolver.resolve(instance_member["type"].as_str().unwrap());
-                    match parse_type_definition_no_error(&type_str) {
-                        Ok(ty) => ports.push(Port {
-                            dir: PortDir::from_str(direction).unwrap(),
-                            name: port_name.to_string(),
-                            ty,
-                        }),
-                        Err(e) => {
-                            if skip_unsupported {
-                                continue;
-                            } else {
-                                panic!("{}", e);
-                            }
-                        }
+        let ports = try_body_ports(&type_resolver, body, skip_unsupported)?;
+        insert_to_vacant(&mut ports_map, module_name.to_string(), ports)
+            .map_err(|_| format!("Duplicate definition of module: {module_name}"))?;
+    }
+
+    Ok(ports_map)
+}
+
+/// Extracts the ports declared by one instance body.
+pub(crate) fn body_ports(
+    type_resolver: &TypeResolver,
+    body: &Value,
+    skip_unsupported: bool,
+) -> Vec<Port> {
+    try_body_ports(type_resolver, body, skip_unsupported).unwrap_or_else(|e| panic!("{}", e))
+}
+
+fn try_body_ports(
+    type_resolver: &TypeResolver,
+    body: &Value,
+    skip_unsupported: bool,
+) -> Result<Vec<Port>, String> {
+    let mut ports = Vec::new();
+    for instance_member in MemberIter::new(body, &["Port", "MultiPort", "InterfacePort"]) {
+        let kind = instance_member["kind"].as_str().unwrap();
+        match kind {
+            "Port" | "MultiPort" => match extract_port(type_resolver, instance_member) {
+                Ok(port) => ports.push(port),
+                Err(e) => {
+                    if skip_unsupported {
+                        continue;
+                    } else {
+                        return Err(e.to_string());
                     }
                 }
-                "InterfacePort" => {
-                    if !skip_unsupported {
-                        panic!("Interface ports are not currently supported.")
-                    }
+            },
+            "InterfacePort" => {
+                if !skip_unsupported {
+                    return Err("Interface ports are not currently supported.".to_string());
                 }
-                _ => continue,
             }
+            _ => continue,
         }
-        insert_to_vacant(&mut ports_map, module_name.to_string(), ports)
-            .unwrap_or_else(|_| panic!("Duplicate definition of module: {module_name}"));
     }
+    Ok(ports)
+}
+
+/// Extracts a `Port` or `MultiPort` member of an instance body.
+fn extract_port(type_resolver: &TypeResolver, port: &Value) -> Result<Port, Box<dyn Error>> {
+    let name = port["name"].as_str().ok_or("Port without a name")?;
+    let direction = port["direction"]
+        .as_str()
+        .ok_or("Port without a direction")?;
+    let ty = type_resolver.build_type(port["type"].as_str().ok_or("Port without a type")?)?;
+
+    let (kind, expression) = if port["kind"].as_str() == Some("MultiPort") {
+        let parts = port["ports"]
+            .as_array()
+            .ok_or("Multi-port without ports")?
+            .iter()
+            .map(|part| port_expression(type_resolver, part).ok_or("Unsupported port expression"))
+            .collect::<Result<Vec<_>, _>>()?;
+        (None, Some(format!("{{{}}}", parts.join(", "))))
+    } else {
+        let internal = type_resolver.resolve_node(&port["internalSymbol"]);
+        let kind = match internal["kind"].as_str() {
+            Some("Net") => link_name(&internal["netType"]).map(|net_type| PortKind::Net {
+                net_type: net_type.to_string(),
+            }),
+            Some("Variable") => Some(PortKind::Variable),
+            _ => None,
+        };
+        let expression = port_expression(type_resolver, port).filter(|expr| expr != name);
+        (kind, expression)
+    };
 
-    ports_map
+    // Attributes on an ANSI port declaration may be attached to the net or
+    // variable it declares rather than to the port itself.
+    let mut attributes = extract_attributes(port);
+    if attributes.is_empty() {
+        attributes = extract_attributes(type_resolver.resolve_node(&port["internalSymbol"]));
+    }
+
+    Ok(Port {
+        dir: PortDir::from_str(direction)?,
+        name: name.to_string(),
+        ty,
+        kind,
+        default: render_expression(&port["initializer"]),
+        expression,
+        location: source_location(port),
+        attributes,
+    })
+}
+
+/// Renders what a port connects to inside the module: its internal
+/// expression, or else the name of its internal symbol.
+fn port_expression(type_resolver: &TypeResolver, port: &Value) -> Option<String> {
+    if !port["internalExpr"].is_null() {
+        return render_expression(&port["internalExpr"]);
+    }
+    let internal = type_resolver.resolve_node(&port["internalSymbol"]);
+    match internal {
+        Value::Object(_) => internal["name"].as_str(),
+        _ => link_name(internal),
+    }
+    .map(str::to_string)
 }
 
 pub fn extract_parameter_defs_from_value(
     value: &Value,
     skip_unsupported: bool,
+) -> HashMap<String, Vec<ParameterDef>> {
+    parameter_defs(value, skip_unsupported, &[])
+}
+
+/// Extracts the parameters of each top-level instance, marking those named
+/// in `overrides`. Like Slang's `-G`, an override applies to every
+/// non-local parameter of that name.
+fn parameter_defs(
+    value: &Value,
+    skip_unsupported: bool,
+    overrides: &[(&str, &str)],
 ) -> HashMap<String, Vec<ParameterDef>> {
     let mut parameters_map = HashMap::new();
     let type_resolver = TypeResolver::new(value);
@@ -194,25 +379,8 @@ pub fn extract_parameter_defs_from_value(
         if module_name.is_empty() {
             continue;
         }
-        let mut parameters = Vec::new();
         let body = type_resolver.resolve_node(&member["body"]);
-        for instance_member in MemberIter::new(body, &["Parameter"]) {
-            let parameter_name = instance_member["name"].as_str().unwrap();
-            let type_str = type_resolver.resolve(instance_member["type"].as_str().unwrap());
-            match parse_type_definition_no_error(&type_str) {
-                Ok(ty) => parameters.push(ParameterDef {
-                    name: parameter_name.to_string(),
-                    ty,
-                }),
-                Err(e) => {
-                    if skip_unsupported {
-                        continue;
-                    } else {
-                        panic!("{}", e);
-                    }
-                }
-            }
-        }
+        let parameters = body_parameter_defs(&type_resolver, body, skip_unsupported, overrides);
         insert_to_vacant(&mut parameters_map, module_name.to_string(), parameters)
             .unwrap_or_else(|_| panic!("Duplicate definition of module: {module_name}"));
     }
@@ -220,12 +388,61 @@ pub fn extract_parameter_defs_from_value(
     parameters_map
 }
 
+/// Extracts the parameters of one instance body, marking those named in
+/// `overrides`.
+pub(crate) fn body_parameter_defs(
+    type_resolver: &TypeResolver,
+    body: &Value,
+    skip_unsupported: bool,
+    overrides: &[(&str, &str)],
+) -> Vec<ParameterDef> {
+    let mut parameters = Vec::new();
+    for instance_member in MemberIter::new(body, &["Parameter", "TypeParameter"]) {
+        let parameter_name = instance_member["name"].as_str().unwrap();
+        let is_local = instance_member["isLocal"].as_bool() == Some(true);
+        let source = if !is_local && overrides.iter().any(|(name, _)| *name == parameter_name) {
+            ParameterSource::CommandLine
+        } else if instance_member["isOverridden"].as_bool() == Some(true) {
+            ParameterSource::Instance
+        } else {
+            ParameterSource::Default
+        };
+        let type_text = instance_member["type"].as_str().unwrap();
+        let ty = match type_resolver.build_type(type_text) {
+            Ok(ty) => Some(ty),
+            Err(_) if matches!(type_text, "real" | "shortreal" | "realtime" | "string") => None,
+            Err(e) => {
+                if skip_unsupported {
+                    continue;
+                } else {
+                    panic!("{}", e);
+                }
+            }
+        };
+        parameters.push(ParameterDef {
+            name: parameter_name.to_string(),
+            value: instance_member["value"].as_str().map(str::to_string),
+            kind: match instance_member["kind"].as_str() {
+                Some("TypeParameter") => ParameterKind::Type,
+                _ => ParameterKind::Value,
+            },
+            ty,
+            is_local,
+            is_port: instance_member["isPort"].as_bool() == Some(true),
+            source,
+            location: source_location(instance_member),
+            attributes: extract_attributes(instance_member),
+        });
+    }
+    parameters
+}
+
 pub fn extract_parameter_defs(
     cfg: &crate::SlangConfig,
     skip_unsupported: bool,
 ) -> HashMap<String, Vec<ParameterDef>> {
     let result = crate::run_slang(cfg).unwrap();
-    extract_parameter_defs_from_value(&result, skip_unsupported)
+    parameter_defs(&result, skip_unsupported, cfg.parameters)
 }
 
 pub fn extract_modules_from_value(value: &Value) -> Result<Vec<String>, Box<dyn Error>> {
@@ -254,6 +471,38 @@ pub fn extract_modules(cfg: &crate::SlangConfig) -> Result<Vec<String>, Box<dyn
     extract_modules_from_value(&result)
 }
 
+/// Returns the attributes on each module declaration, keyed by module name.
+/// Modules without attributes are included with an empty list.
+pub fn extract_module_attributes_from_value(
+    value: &Value,
+) -> Result<HashMap<String, Vec<Attribute>>, Box<dyn Error>> {
+    let definitions = value
+        .get("definitions")
+        .and_then(|v| v.as_array())
+        .ok_or("JSON parsing failed")?;
+
+    let mut modules = HashMap::new();
+
+    for definition in definitions {
+        if definition.get("kind").and_then(|v| v.as_str()) == Some("Definition")
+            && definition.get("definitionKind").and_then(|v| v.as_str()) == Some("Module")
+        {
+            if let Some(name) = definition.get("name").and_then(|v| v.as_str()) {
+                modules.insert(name.to_string(), extract_attributes(definition));
+            }
+        }
+    }
+
+    Ok(modules)
+}
+
+pub fn extract_module_attributes(
+    cfg: &crate::SlangConfig,
+) -> Result<HashMap<String, Vec<Attribute>>, Box<dyn Error>> {
+    let result = crate::run_slang(cfg)?;
+    extract_module_attributes_from_value(&result)
+}
+
 /// Expands the address-based type links emitted by Slang v10 and newer back
 /// into the self-contained textual format consumed by this crate's type parser.
 ///
@@ -371,19 +620,39 @@ impl<'a> TypeResolver<'a> {
     fn render_link(&self, address: u64, display_name: &str) -> Option<String> {
         let node = *self.by_address.get(&address)?;
         match node.get("kind").and_then(Value::as_str)? {
-            "TypeAlias" => self.resolve_alias(node, display_name),
+            "TypeAlias" => {
+                let rendered = self.resolve_alias(node, display_name)?;
+                if dimension_start(display_name).is_some() {
+                    Some(rendered)
+                } else {
+                    // Record the typedef name for `Type::aliases`.
+                    Some(format!("@({display_name}){rendered}"))
+                }
+            }
             "EnumType" => self.render_enum(node, display_name),
+            "PackedStructType" | "UnpackedStructType" | "PackedUnionType" | "UnpackedUnionType" => {
+                self.render_aggregate(node, display_name)
+            }
             _ => None,
         }
     }
 
     /// Applies a typedef's public name and use-site dimensions to its resolved
     /// target text.
+    ///
+    /// Alias annotations on the target are kept only if this use site adds no
+    /// dimensions, since otherwise they no longer name the whole type.
     fn render_alias_target(&self, target: &str, display_name: &str) -> Option<String> {
         let display_dimension_start = dimension_start(display_name);
         let display_dimensions = display_dimension_start
             .map(|index| &display_name[index..])
             .unwrap_or("");
+        let (aliases, target) = split_aliases(target);
+        let aliases = if display_dimensions.is_empty() {
+            aliases
+        } else {
+            ""
+        };
         let display_name = display_dimension_start
             .map(|index| &display_name[..index])
             .unwrap_or(display_name);
@@ -405,7 +674,7 @@ impl<'a> TypeResolver<'a> {
                 .map(|index| &target_suffix[index..])
                 .unwrap_or("");
             Some(format!(
-                "{}{display_name}{}",
+                "{aliases}{}{display_name}{}",
                 &target[..=close],
                 merge_dimensions(target_dimensions, display_dimensions)
             ))
@@ -418,12 +687,36 @@ impl<'a> TypeResolver<'a> {
                 .map(|index| &target[..index])
                 .unwrap_or(target);
             Some(format!(
-                "{target}{}",
+                "{aliases}{target}{}",
                 merge_dimensions(target_dimensions, display_dimensions)
             ))
         }
     }
 
+    /// Reconstructs the legacy textual struct or union syntax from a structured
+    /// type node, whose fields are `Field` members.
+    fn render_aggregate(&self, node: &Value, display_name: &str) -> Option<String> {
+        let kind = node.get("kind")?.as_str()?;
+        let fields = node
+            .get("members")?
+            .as_array()?
+            .iter()
+            .filter(|member| member.get("kind").and_then(Value::as_str) == Some("Field"))
+            .map(|member| {
+                let name = member.get("name")?.as_str()?;
+                let ty = self.resolve(member.get("type")?.as_str()?);
+                Some(format!("{ty} {name};"))
+            })
+            .collect::<Option<String>>()?;
+        let keyword = match kind {
+            "PackedStructType" => "struct packed",
+            "UnpackedStructType" => "struct",
+            "PackedUnionType" => "union packed",
+            _ => "union",
+        };
+        Some(format!("{keyword}{{{fields}}}{display_name}"))
+    }
+
     /// Reconstructs the legacy textual enum syntax from a v10+ `EnumType` node.
     ///
     /// Unlike struct and union aliases, Slang no longer leaves a complete enum
@@ -455,6 +748,18 @@ impl<'a> TypeResolver<'a> {
     }
 }
 
+/// Splits the leading `@(name)` alias annotations from a resolved type.
+fn split_aliases(type_text: &str) -> (&str, &str) {
+    let mut rest = type_text;
+    while let Some(annotation) = rest.strip_prefix("@(") {
+        match annotation.find(')') {
+            Some(close) => rest = &annotation[close + 1..],
+            None => break,
+        }
+    }
+    type_text.split_at(type_text.len() - rest.len())
+}
+
 /// Finds the first packed (`[... ]`) or unpacked (`$[...]`) dimension.
 ///
 /// Slang's generated nominal type names can themselves contain `$`, so only
@@ -495,7 +800,7 @@ fn merge_dimensions(target: &str, display: &str) -> String {
 /// from their fixed-width two's-complement representation before rendering.
 /// Slang v11 can also emit unsized enum values as plain decimal strings; these
 /// have SystemVerilog's default signed 32-bit integer representation.
-fn parse_integer_literal(value: &str) -> Option<(usize, bool, BigInt)> {
+pub(crate) fn parse_integer_literal(value: &str) -> Option<(usize, bool, BigInt)> {
     let Some((width, digits)) = value.split_once('\'') else {
         let digits = value.replace('_', "");
         return Some((32, true, BigInt::from_str_radix(&digits, 10).ok()?));
@@ -604,15 +909,18 @@ mod resolver_tests {
 
         assert_eq!(
             resolver.resolve("200 outer_t"),
-            "struct packed{struct packed{logic[7:0] data;}inner_t value;}outer_t"
+            "@(outer_t)struct packed{@(inner_t)struct packed{logic[7:0] data;}inner_t value;}outer_t"
         );
         assert_eq!(
             resolver.resolve("300 outer_array_t[1:0]"),
             "struct packed{logic[7:0] data;}outer_array_t[3:0][1:0]"
         );
         assert_eq!(resolver.resolve("400 unpacked_t$[1:0]"), "logic$[3:0][1:0]");
-        assert!(parse_type_definition(&resolver.resolve("400 unpacked_t$[1:0]")).is_ok());
-        assert_eq!(resolver.resolve("500 nested_t"), "logic$[3:0][1:0]");
+        assert!(parse_resolved_type(&resolver.resolve("400 unpacked_t$[1:0]")).is_ok());
+        assert_eq!(
+            resolver.resolve("500 nested_t"),
+            "@(nested_t)logic$[3:0][1:0]"
+        );
     }
 
     #[test]
@@ -627,8 +935,53 @@ mod resolver_tests {
         let resolver = TypeResolver::new(&ast);
         let resolved = resolver.resolve("100 top.byte_t");
 
-        assert_eq!(resolved, "struct packed{logic[7:0] data;}top.byte_t");
-        assert!(parse_type_definition(&resolved).is_ok());
+        assert_eq!(
+            resolved,
+            "@(top.byte_t)struct packed{logic[7:0] data;}top.byte_t"
+        );
+        assert!(parse_resolved_type(&resolved).is_ok());
+    }
+
+    #[test]
+    fn records_slang_v11_typedef_alias_chains() {
+        let ast = json!({
+            "nodes": [
+                { "kind": "TypeAlias", "addr": 100, "target": "logic[39:0]" },
+                { "kind": "TypeAlias", "addr": 200, "target": "100 addr_t" },
+                {
+                    "kind": "TypeAlias",
+                    "addr": 300,
+                    "target": "struct packed{200 pkg::paddr_t base;100 addr_t[1:0] pair;}generated$1"
+                }
+            ]
+        });
+        let resolver = TypeResolver::new(&ast);
+
+        let paddr = parse_resolved_type(&resolver.resolve("200 pkg::paddr_t")).unwrap();
+        assert_eq!(paddr.aliases(), &["pkg::paddr_t", "addr_t"]);
+        assert_eq!(paddr.typedef_name(), Some("pkg::paddr_t"));
+        assert_eq!(paddr.width().unwrap(), 40);
+
+        // Use-site dimensions mean the typedef no longer names the whole type.
+        let array = parse_resolved_type(&resolver.resolve("200 pkg::paddr_t$[3:0]")).unwrap();
+        assert!(array.aliases().is_empty());
+
+        let Type::Struct { fields, .. } =
+            parse_resolved_type(&resolver.resolve("300 pkg::region_t")).unwrap()
+        else {
+            panic!("expected a struct");
+        };
+        assert_eq!(fields[0].ty.aliases(), &["pkg::paddr_t", "addr_t"]);
+        assert!(fields[1].ty.aliases().is_empty());
+        assert_eq!(fields[1].ty.width().unwrap(), 80);
+
+        // The typedef markers are internal to the resolver.
+        assert!(parse_type_definition("@(addr_t)logic[39:0]").is_err());
+        assert_eq!(
+            paddr,
+            parse_type_definition("logic[39:0]").unwrap(),
+            "aliases are not compared"
+        );
     }
 
     #[test]
@@ -649,7 +1002,8 @@ mod resolver_tests {
                             {
                                 "kind": "Parameter",
                                 "name": "WIDTH",
-                                "type": "int"
+                                "type": "int",
+                                "value": "8"
                             }
                         ]
                     },
@@ -662,6 +1016,225 @@ mod resolver_tests {
         assert_eq!(ports["top"][0].name, "data");
         let parameters = extract_parameter_defs_from_value(&ast, false);
         assert_eq!(parameters["top"][0].name, "WIDTH");
+        assert_eq!(parameters["top"][0].value.as_deref(), Some("8"));
+    }
+
+    #[test]
+    fn extracts_port_kinds_defaults_and_expressions() {
+        let ast = json!({
+            "design": {
+                "members": [{
+                    "kind": "Instance",
+                    "name": "foo",
+                    "body": "500 foo",
+                }]
+            },
+            "definitions": [{
+                "kind": "InstanceBody",
+                "name": "foo",
+                "addr": 500,
+                "members": [
+                    {
+                        "kind": "Port",
+                        "name": "en",
+                        "type": "logic",
+                        "direction": "In",
+                        "internalSymbol": "10 en",
+                        "initializer": { "kind": "IntegerLiteral", "value": "1'b1" }
+                    },
+                    {
+                        "kind": "Port",
+                        "name": "q",
+                        "type": "logic[3:0]",
+                        "direction": "Ref",
+                        "internalSymbol": "11 q"
+                    },
+                    {
+                        "kind": "Port",
+                        "name": "a",
+                        "type": "logic[1:0]",
+                        "direction": "Out",
+                        "internalSymbol": "12 b"
+                    },
+                    {
+                        "kind": "MultiPort",
+                        "name": "c",
+                        "type": "logic[1:0]",
+                        "direction": "In",
+                        "ports": [
+                            { "kind": "Port", "name": "", "internalSymbol": "13 d" },
+                            {
+                                "kind": "Port",
+                                "name": "",
+                                "internalSymbol": "12 b",
+                                "internalExpr": {
+                                    "kind": "ElementSelect",
+                                    "value": { "kind": "NamedValue", "symbol": "12 b" },
+                                    "selector": { "kind": "IntegerLiteral", "value": "0" }
+                                }
+                            }
+                        ]
+                    },
+                    { "kind": "Net", "name": "en", "addr": 10, "netType": "1 tri" },
+                    { "kind": "Variable", "name": "q", "addr": 11 },
+                    {
+                        "kind": "Net",
+                        "name": "b",
+                        "addr": 12,
+                        "netType": { "kind": "NetType", "name": "wand" }
+                    },
+                    { "kind": "Net", "name": "d", "addr": 13, "netType": "1 wire" }
+                ]
+            }]
+        });
+
+        let ports = &extract_ports_from_value(&ast, false)["foo"];
+        let summary: Vec<_> = ports
+            .iter()
+            .map(|port| {
+                (
+                    port.dir,
+                    port.name.as_str(),
+                    port.kind.clone(),
+                    port.default.as_deref(),
+                    port.expression.as_deref(),
+                )
+            })
+            .collect();
+        let net = |net_type: &str| {
+            Some(PortKind::Net {
+                net_type: net_type.to_string(),
+            })
+        };
+        assert_eq!(
+            summary,
+            vec![
+                (PortDir::Input, "en", net("tri"), Some("1'b1"), None),
+                (PortDir::Ref, "q", Some(PortKind::Variable), None, None),
+                (PortDir::Output, "a", net("wand"), None, Some("b")),
+                (PortDir::Input, "c", None, None, Some("{d, b[0]}")),
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_port_and_module_attributes() {
+        let ast = json!({
+            "design": {
+                "members": [{
+                    "kind": "Instance",
+                    "name": "foo",
+                    "body": {
+                        "members": [
+                            {
+                                "kind": "Port",
+                                "name": "a",
+                                "type": "logic",
+                                "direction": "In",
+                                "internalSymbol": "10 a"
+                            },
+                            {
+                                "kind": "Port",
+                                "name": "b",
+                                "type": "logic",
+                                "direction": "Out",
+                                "internalSymbol": "11 b",
+                                "attributes": [{ "name": "keep", "value": "1'b1" }]
+                            },
+                            {
+                                "kind": "Net",
+                                "name": "a",
+                                "addr": 10,
+                                "netType": "1 wire",
+                                "attributes": [{ "name": "mark_debug", "value": "\"true\"" }]
+                            },
+                            { "kind": "Variable", "name": "b", "addr": 11 }
+                        ]
+                    }
+                }]
+            },
+            "definitions": [
+                {
+                    "kind": "Definition",
+                    "definitionKind": "Module",
+                    "name": "foo",
+                    "attributes": [{ "name": "dont_touch", "value": "1'b1" }]
+                },
+                { "kind": "Definition", "definitionKind": "Module", "name": "bar" }
+            ]
+        });
+
+        let ports = &extract_ports_from_value(&ast, false)["foo"];
+        assert_eq!(
+            ports[0].attributes,
+            vec![(
+                "mark_debug".to_string(),
+                Some(ConstValue::String("true".to_string()))
+            )]
+        );
+        assert_eq!(
+            ports[1].attributes,
+            vec![("keep".to_string(), Some(ConstValue::integer(1, false, 1)))]
+        );
+
+        let modules = extract_module_attributes_from_value(&ast).unwrap();
+        assert_eq!(modules["foo"][0].0, "dont_touch");
+        assert!(modules["bar"].is_empty());
+    }
+
+    #[test]
+    fn extracts_type_parameters_and_overrides() {
+        let ast = json!({
+            "design": {
+                "members": [{
+                    "kind": "Instance",
+                    "name": "fifo",
+                    "body": {
+                        "members": [
+                            { "kind": "TypeParameter", "name": "T", "type": "logic[7:0]" },
+                            {
+                                "kind": "TypeParameter",
+                                "name": "U",
+                                "type": "logic[3:0]",
+                                "isLocal": true
+                            },
+                            {
+                                "kind": "Parameter",
+                                "name": "DEPTH",
+                                "type": "int",
+                                "value": "16",
+                                "isPort": true,
+                                "isOverridden": true
+                            }
+                        ]
+                    }
+                }]
+            }
+        });
+
+        let parameters = extract_parameter_defs_from_value(&ast, false);
+        assert_eq!(parameters["fifo"][0].kind, ParameterKind::Type);
+        assert_eq!(
+            parameters["fifo"][0].ty,
+            Some(parse_type_definition("logic[7:0]").unwrap())
+        );
+        assert!(
+            parameters["fifo"][..2]
+                .iter()
+                .all(|p| p.source == ParameterSource::Default)
+        );
+
+        let parameters = parameter_defs(&ast, false, &[("T", "logic[1:0]"), ("U", "logic")]);
+        let [t, u, depth] = &parameters["fifo"][..] else {
+            panic!("expected three parameters");
+        };
+        assert_eq!(t.source, ParameterSource::CommandLine);
+        assert_eq!(t.kind, ParameterKind::Type);
+        assert_eq!(u.source, ParameterSource::Default);
+        assert!(u.is_local);
+        assert_eq!(depth.source, ParameterSource::Instance);
+        assert!(depth.is_port);
+        assert_eq!(depth.kind, ParameterKind::Value);
     }
 
     #[test]
@@ -0,0 +1,693 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Type matching, equivalence and compatibility as defined in IEEE 1800-2017
+//! section 6.22.
+//!
+//! Struct, union and enum types are identified by name, since extracted types
+//! carry no declaration identity: two types from separate extraction runs
+//! match if they have the same name and the same members.
+//!
+//! The `Type` model does not record whether a type is 2-state or 4-state:
+//! `int` and `logic signed [31:0]` build the same `Type`. Two types are only
+//! known to agree on it when they are the same struct, union or enum, or were
+//! declared through the same typedef. Otherwise a check that depends on it
+//! fails with an undecided [`TypeMismatch`].
+
+use super::type_extract::{Range, Type};
+use std::error::Error;
+use std::fmt;
+
+/// The strongest relation that holds between two types, from strongest to
+/// weakest. Each relation implies all of the weaker ones.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum TypeRelation {
+    Matching,
+    Equivalent,
+    AssignmentCompatible,
+    CastCompatible,
+}
+
+/// Explains why two types are not related, e.g. ``field `len` width 8 vs 12``.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TypeMismatch {
+    message: String,
+    undecided: bool,
+}
+
+impl TypeMismatch {
+    fn new(path: &str, detail: String) -> Self {
+        let message = if path.is_empty() {
+            detail
+        } else {
+            format!("field `{path}` {detail}")
+        };
+        TypeMismatch {
+            message,
+            undecided: false,
+        }
+    }
+
+    fn undecided(path: &str) -> Self {
+        TypeMismatch {
+            undecided: true,
+            ..TypeMismatch::new(path, "2-state or 4-state unknown".to_string())
+        }
+    }
+
+    /// Whether the types may still be related: they agree in everything the
+    /// `Type` model records, but the relation also requires both to be 2-state
+    /// or both to be 4-state, which it does not record.
+    pub fn is_undecided(&self) -> bool {
+        self.undecided
+    }
+}
+
+impl fmt::Display for TypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for TypeMismatch {}
+
+impl Type {
+    /// Checks whether `self` and `other` are matching types (6.22.1).
+    pub fn matches(&self, other: &Type) -> Result<(), TypeMismatch> {
+        matching(self, other, "", false)
+    }
+
+    /// Checks whether `self` and `other` are equivalent types (6.22.2).
+    pub fn equivalent(&self, other: &Type) -> Result<(), TypeMismatch> {
+        equivalent(self, other, "")
+    }
+
+    /// Checks whether a value of type `source` can be assigned to `self`
+    /// without a cast (6.22.3), for example whether an output port of type
+    /// `source` can drive an input port of type `self`.
+    pub fn assignment_compatible(&self, source: &Type) -> Result<(), TypeMismatch> {
+        assignment_compatible(self, source)
+    }
+
+    /// Checks whether a value of type `source` can be cast to `self`
+    /// (6.22.4), including bit-stream casts between types of the same size.
+    pub fn cast_compatible(&self, source: &Type) -> Result<(), TypeMismatch> {
+        cast_compatible(self, source)
+    }
+
+    /// Returns the strongest relation known to hold between `self` as the
+    /// target type and `source`, or why a value of type `source` cannot even be
+    /// cast to it. Undecided relations are skipped, so `int` and
+    /// `logic signed [31:0]` are only assignment compatible.
+    pub fn relation(&self, source: &Type) -> Result<TypeRelation, TypeMismatch> {
+        if self.matches(source).is_ok() {
+            Ok(TypeRelation::Matching)
+        } else if self.equivalent(source).is_ok() {
+            Ok(TypeRelation::Equivalent)
+        } else if self.assignment_compatible(source).is_ok() {
+            Ok(TypeRelation::AssignmentCompatible)
+        } else {
+            self.cast_compatible(source)
+                .map(|()| TypeRelation::CastCompatible)
+        }
+    }
+}
+
+/// `same_declaration` is set for the members of two types already known to be
+/// the same declaration, which agree on being 2-state or 4-state.
+fn matching(a: &Type, b: &Type, path: &str, same_declaration: bool) -> Result<(), TypeMismatch> {
+    match (a, b) {
+        (
+            Type::Logic {
+                signed: signed_a, ..
+            },
+            Type::Logic {
+                signed: signed_b, ..
+            },
+        ) => {
+            let (packed_a, packed_b) = (a.packed_dimensions(), b.packed_dimensions());
+            if packed_a != packed_b {
+                let (width_a, width_b) = (elements(packed_a), elements(packed_b));
+                return Err(TypeMismatch::new(
+                    path,
+                    if packed_a.len() == packed_b.len() && width_a != width_b {
+                        format!("width {width_a} vs {width_b}")
+                    } else {
+                        format!(
+                            "packed dims {} vs {}",
+                            dimensions(packed_a),
+                            dimensions(packed_b)
+                        )
+                    },
+                ));
+            }
+            if signed_a != signed_b {
+                return Err(TypeMismatch::new(
+                    path,
+                    format!("{} vs {}", signedness(*signed_a), signedness(*signed_b)),
+                ));
+            }
+        }
+        (
+            Type::Struct {
+                name: name_a,
+                packed: packed_a,
+                fields: fields_a,
+                ..
+            },
+            Type::Struct {
+                name: name_b,
+                packed: packed_b,
+                fields: fields_b,
+                ..
+            },
+        )
+        | (
+            Type::Union {
+                name: name_a,
+                packed: packed_a,
+                fields: fields_a,
+                ..
+            },
+            Type::Union {
+                name: name_b,
+                packed: packed_b,
+                fields: fields_b,
+                ..
+            },
+        ) => {
+            if name_a != name_b {
+                return Err(TypeMismatch::new(
+                    path,
+                    format!("{} `{name_a}` vs `{name_b}` not matching", kind(a)),
+                ));
+            }
+            if packed_a != packed_b {
+                return Err(TypeMismatch::new(
+                    path,
+                    format!("{} vs {}", packing(*packed_a), packing(*packed_b)),
+                ));
+            }
+            for (field_a, field_b) in fields_a.iter().zip(fields_b) {
+                if field_a.name != field_b.name {
+                    return Err(TypeMismatch::new(
+                        path,
+                        format!("field `{}` vs `{}`", field_a.name, field_b.name),
+                    ));
+                }
+                matching(&field_a.ty, &field_b.ty, &join(path, &field_a.name), true)?;
+            }
+            if fields_a.len() != fields_b.len() {
+                return Err(TypeMismatch::new(
+                    path,
+                    format!("{} vs {} fields", fields_a.len(), fields_b.len()),
+                ));
+            }
+        }
+        (
+            Type::Enum {
+                name: name_a,
+                variants: variants_a,
+                ..
+            },
+            Type::Enum {
+                name: name_b,
+                variants: variants_b,
+                ..
+            },
+        ) => {
+            if name_a != name_b {
+                return Err(TypeMismatch::new(
+                    path,
+                    format!("enum `{name_a}` vs `{name_b}` not matching"),
+                ));
+            }
+            for (variant_a, variant_b) in variants_a.iter().zip(variants_b) {
+                if variant_a.name != variant_b.name {
+                    return Err(TypeMismatch::new(
+                        path,
+                        format!("enum variant `{}` vs `{}`", variant_a.name, variant_b.name),
+                    ));
+                }
+                if variant_a.value != variant_b.value {
+                    return Err(TypeMismatch::new(
+                        path,
+                        format!(
+                            "enum variant `{}` value {} vs {}",
+                            variant_a.name, variant_a.value, variant_b.value
+                        ),
+                    ));
+                }
+                if variant_a.width != variant_b.width {
+                    return Err(TypeMismatch::new(
+                        path,
+                        format!("width {} vs {}", variant_a.width, variant_b.width),
+                    ));
+                }
+            }
+            if variants_a.len() != variants_b.len() {
+                return Err(TypeMismatch::new(
+                    path,
+                    format!("{} vs {} enum variants", variants_a.len(), variants_b.len()),
+                ));
+            }
+        }
+        _ => {
+            return Err(TypeMismatch::new(
+                path,
+                format!("{} vs {}", describe(a), describe(b)),
+            ));
+        }
+    }
+
+    if !matches!(a, Type::Logic { .. }) && a.packed_dimensions() != b.packed_dimensions() {
+        return Err(TypeMismatch::new(
+            path,
+            format!(
+                "packed dims {} vs {}",
+                dimensions(a.packed_dimensions()),
+                dimensions(b.packed_dimensions())
+            ),
+        ));
+    }
+    if a.unpacked_dimensions() != b.unpacked_dimensions() {
+        return Err(TypeMismatch::new(
+            path,
+            format!(
+                "unpacked dims {} vs {}",
+                dimensions(a.unpacked_dimensions()),
+                dimensions(b.unpacked_dimensions())
+            ),
+        ));
+    }
+    if matches!(a, Type::Logic { .. }) && !same_declaration && !same_typedef(a, b) {
+        return Err(TypeMismatch::undecided(path));
+    }
+    Ok(())
+}
+
+fn equivalent(a: &Type, b: &Type, path: &str) -> Result<(), TypeMismatch> {
+    let error = match matching(a, b, path, false) {
+        Ok(()) => return Ok(()),
+        Err(error) => error,
+    };
+
+    if !a.unpacked_dimensions().is_empty() || !b.unpacked_dimensions().is_empty() {
+        // Unpacked arrays only need the same shape; their bounds may differ.
+        unpacked_shape(a, b, path)?;
+        return equivalent(&element(a), &element(b), path);
+    }
+
+    match (packed_integral(a), packed_integral(b)) {
+        (Some((width_a, signed_a)), Some((width_b, signed_b))) => {
+            if width_a == width_b && signed_a == signed_b {
+                return if same_typedef(a, b) {
+                    Ok(())
+                } else {
+                    Err(TypeMismatch::undecided(path))
+                };
+            }
+            if same_nominal(a, b) {
+                // Report the member that differs rather than the total width.
+                return Err(error);
+            }
+            Err(TypeMismatch::new(
+                path,
+                if width_a != width_b {
+                    format!("width {width_a} vs {width_b}")
+                } else {
+                    format!("{} vs {}", signedness(signed_a), signedness(signed_b))
+                },
+            ))
+        }
+        _ => Err(error),
+    }
+}
+
+fn assignment_compatible(target: &Type, source: &Type) -> Result<(), TypeMismatch> {
+    let error = match equivalent(target, source, "") {
+        Ok(()) => return Ok(()),
+        Err(error) => error,
+    };
+
+    if let Type::Enum { name, .. } = target {
+        if target.unpacked_dimensions().is_empty() && !matches!(source, Type::Enum { .. }) {
+            return Err(TypeMismatch::new(
+                "",
+                format!(
+                    "{} is not assignable to enum `{name}` without a cast",
+                    describe(source)
+                ),
+            ));
+        }
+    }
+    if is_integral(target) && is_integral(source) {
+        return match target {
+            Type::Enum { .. } => Err(error),
+            _ => Ok(()),
+        };
+    }
+    Err(error)
+}
+
+fn cast_compatible(target: &Type, source: &Type) -> Result<(), TypeMismatch> {
+    let error = match assignment_compatible(target, source) {
+        Ok(()) => return Ok(()),
+        Err(error) => error,
+    };
+
+    if is_integral(target) && is_integral(source) {
+        return Ok(());
+    }
+    match (bit_stream_width(target), bit_stream_width(source)) {
+        (Some(width_target), Some(width_source)) if width_target == width_source => Ok(()),
+        (Some(width_target), Some(width_source)) => Err(TypeMismatch::new(
+            "",
+            format!("bit-stream width {width_target} vs {width_source}"),
+        )),
+        _ => Err(error),
+    }
+}
+
+/// Checks that two types have the same number and sizes of unpacked
+/// dimensions.
+fn unpacked_shape(a: &Type, b: &Type, path: &str) -> Result<(), TypeMismatch> {
+    let (unpacked_a, unpacked_b) = (a.unpacked_dimensions(), b.unpacked_dimensions());
+    if unpacked_a.len() != unpacked_b.len()
+        || unpacked_a
+            .iter()
+            .zip(unpacked_b)
+            .any(|(range_a, range_b)| length(range_a) != length(range_b))
+    {
+        return Err(TypeMismatch::new(
+            path,
+            format!(
+                "unpacked dims differ: {} vs {}",
+                dimensions(unpacked_a),
+                dimensions(unpacked_b)
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the total width and signedness of a packed type other than an
+/// enum, which packed types are equivalent to when both agree.
+fn packed_integral(ty: &Type) -> Option<(usize, bool)> {
+    match ty {
+        Type::Logic { signed, .. } => Some((ty.width().ok()?, *signed)),
+        Type::Struct { packed: true, .. } | Type::Union { packed: true, .. } => {
+            Some((ty.width().ok()?, false))
+        }
+        _ => None,
+    }
+}
+
+/// Integral types are the packed types, including enums.
+fn is_integral(ty: &Type) -> bool {
+    ty.unpacked_dimensions().is_empty()
+        && match ty {
+            Type::Logic { .. } | Type::Enum { .. } => true,
+            Type::Struct { packed, .. } | Type::Union { packed, .. } => *packed,
+        }
+}
+
+/// Returns the number of bits in a bit-stream type, or `None` for types that
+/// cannot be streamed, such as unpacked unions (IEEE 1800-2017 section
+/// 6.24.3). Port flattening follows the same rule.
+pub(crate) fn bit_stream_width(ty: &Type) -> Option<usize> {
+    let count: usize = ty.unpacked_dimensions().iter().map(length).product();
+    let element = element(ty);
+    let width = match &element {
+        Type::Struct {
+            packed: false,
+            fields,
+            ..
+        } => fields
+            .iter()
+            .map(|field| bit_stream_width(&field.ty))
+            .sum::<Option<usize>>()?,
+        Type::Union { packed: false, .. } => return None,
+        _ => element.width().ok()?,
+    };
+    Some(width * count)
+}
+
+/// Whether both types were declared through the same typedef, compared by
+/// its unqualified name like struct, union and enum names are.
+fn same_typedef(a: &Type, b: &Type) -> bool {
+    let declaration = |ty: &Type| {
+        ty.aliases()
+            .last()
+            .map(|alias| alias.rsplit("::").next().unwrap_or(alias).to_string())
+    };
+    declaration(a).is_some() && declaration(a) == declaration(b)
+}
+
+fn same_nominal(a: &Type, b: &Type) -> bool {
+    match (a, b) {
+        (Type::Struct { name: a, .. }, Type::Struct { name: b, .. })
+        | (Type::Union { name: a, .. }, Type::Union { name: b, .. })
+        | (Type::Enum { name: a, .. }, Type::Enum { name: b, .. }) => a == b,
+        _ => false,
+    }
+}
+
+/// Returns `ty` without its unpacked dimensions.
+fn element(ty: &Type) -> Type {
+    let mut ty = ty.clone();
+    ty.dimensions_mut().1.clear();
+    ty
+}
+
+/// Renders a short SystemVerilog-like description of `ty` for messages.
+fn describe(ty: &Type) -> String {
+    let base = match ty {
+        Type::Logic { signed: true, .. } => "logic signed".to_string(),
+        Type::Logic { .. } => "logic".to_string(),
+        Type::Struct { name, .. } | Type::Union { name, .. } | Type::Enum { name, .. } => {
+            format!("{} `{name}`", kind(ty))
+        }
+    };
+    let mut description = format!("{base}{}", dimensions(ty.packed_dimensions()));
+    if !ty.unpacked_dimensions().is_empty() {
+        description.push_str(&format!(" {}", dimensions(ty.unpacked_dimensions())));
+    }
+    description
+}
+
+fn kind(ty: &Type) -> &'static str {
+    match ty {
+        Type::Logic { .. } => "logic",
+        Type::Struct { .. } => "struct",
+        Type::Union { .. } => "union",
+        Type::Enum { .. } => "enum",
+    }
+}
+
+fn signedness(signed: bool) -> &'static str {
+    if signed { "signed" } else { "unsigned" }
+}
+
+fn packing(packed: bool) -> &'static str {
+    if packed { "packed" } else { "unpacked" }
+}
+
+fn dimensions(ranges: &[Range]) -> String {
+    ranges
+        .iter()
+        .map(|range| format!("[{}:{}]", range.msb, range.lsb))
+        .collect()
+}
+
+fn elements(ranges: &[Range]) -> usize {
+    ranges.iter().map(length).product()
+}
+
+fn length(range: &Range) -> usize {
+    ((range.msb - range.lsb).abs() + 1) as usize
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{path}.{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::{parse_resolved_type, parse_type_definition};
+
+    fn ty(definition: &str) -> Type {
+        parse_type_definition(definition).unwrap()
+    }
+
+    fn mismatch(result: Result<(), TypeMismatch>) -> String {
+        result.unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_logic() {
+        let byte_t = parse_resolved_type("@(pkg::byte_t)logic[7:0]").unwrap();
+        let byte_t_in_pkg = parse_resolved_type("@(byte_t)logic[7:0]").unwrap();
+        assert_eq!(byte_t.relation(&byte_t_in_pkg), Ok(TypeRelation::Matching));
+
+        // Either side could be `bit` rather than `logic`.
+        let byte = ty("logic[7:0]");
+        assert!(byte.matches(&byte.clone()).unwrap_err().is_undecided());
+        assert_eq!(
+            byte.relation(&ty("logic[7:0]")),
+            Ok(TypeRelation::AssignmentCompatible)
+        );
+        assert_eq!(
+            mismatch(byte.matches(&ty("logic[8:1]"))),
+            "packed dims [7:0] vs [8:1]"
+        );
+        assert!(!byte.matches(&ty("logic[8:1]")).unwrap_err().is_undecided());
+        assert_eq!(
+            mismatch(byte.equivalent(&ty("logic[8:1]"))),
+            "2-state or 4-state unknown"
+        );
+        assert_eq!(
+            byte.relation(&ty("logic[11:0]")),
+            Ok(TypeRelation::AssignmentCompatible)
+        );
+        assert_eq!(
+            mismatch(byte.equivalent(&ty("logic[11:0]"))),
+            "width 8 vs 12"
+        );
+        assert_eq!(
+            mismatch(byte.equivalent(&ty("logic signed[7:0]"))),
+            "unsigned vs signed"
+        );
+        assert!(
+            byte.equivalent(&ty("logic[1:0][3:0]"))
+                .unwrap_err()
+                .is_undecided()
+        );
+        assert!(
+            ty("int")
+                .matches(&ty("logic signed[31:0]"))
+                .unwrap_err()
+                .is_undecided()
+        );
+        assert_eq!(
+            ty("int").relation(&ty("logic signed[31:0]")),
+            Ok(TypeRelation::AssignmentCompatible)
+        );
+    }
+
+    #[test]
+    fn test_structs() {
+        let pkt = ty("struct packed{logic[7:0] len;logic valid;}pkg::pkt_t");
+        let wide_pkt = ty("struct packed{logic[11:0] len;logic valid;}pkg::pkt_t");
+        assert_eq!(
+            mismatch(pkt.equivalent(&wide_pkt)),
+            "field `len` width 8 vs 12"
+        );
+        assert_eq!(
+            pkt.relation(&wide_pkt),
+            Ok(TypeRelation::AssignmentCompatible)
+        );
+
+        assert_eq!(pkt.relation(&pkt.clone()), Ok(TypeRelation::Matching));
+
+        // Packed structs are equivalent to bit vectors of the same width if
+        // both are 2-state or both are 4-state.
+        assert!(
+            pkt.equivalent(&ty("logic[8:0]"))
+                .unwrap_err()
+                .is_undecided()
+        );
+        assert_eq!(
+            pkt.relation(&ty("logic[8:0]")),
+            Ok(TypeRelation::AssignmentCompatible)
+        );
+
+        let nested =
+            ty("struct{struct packed{logic[7:0] len;logic valid;}pkg::pkt_t hdr;}pkg::msg_t");
+        let other =
+            ty("struct{struct packed{logic[3:0] len;logic valid;}pkg::pkt_t hdr;}pkg::msg_t");
+        assert_eq!(
+            mismatch(nested.assignment_compatible(&other)),
+            "field `hdr.len` width 8 vs 4"
+        );
+        assert_eq!(
+            mismatch(nested.assignment_compatible(&ty("logic[8:0]"))),
+            "struct `pkg::msg_t` vs logic[8:0]"
+        );
+        assert_eq!(
+            nested.relation(&ty("logic[8:0]")),
+            Ok(TypeRelation::CastCompatible)
+        );
+        assert_eq!(
+            mismatch(nested.cast_compatible(&ty("logic[7:0]"))),
+            "bit-stream width 9 vs 8"
+        );
+        assert_eq!(
+            mismatch(ty("struct{logic a;}a_t").matches(&ty("struct{logic a;}b_t"))),
+            "struct `a_t` vs `b_t` not matching"
+        );
+    }
+
+    #[test]
+    fn test_enums() {
+        let mode = ty("enum{A=2'd0,B=2'd1}mode_t");
+        assert_eq!(mode.relation(&mode.clone()), Ok(TypeRelation::Matching));
+        assert_eq!(
+            mismatch(mode.assignment_compatible(&ty("enum{A=2'd0,B=2'd1}mode_e"))),
+            "enum `mode_t` vs `mode_e` not matching"
+        );
+        assert_eq!(
+            mismatch(mode.assignment_compatible(&ty("logic[1:0]"))),
+            "logic[1:0] is not assignable to enum `mode_t` without a cast"
+        );
+        assert_eq!(
+            mode.relation(&ty("logic[1:0]")),
+            Ok(TypeRelation::CastCompatible)
+        );
+        assert_eq!(
+            ty("logic[1:0]").relation(&mode),
+            Ok(TypeRelation::AssignmentCompatible)
+        );
+        assert_eq!(
+            mismatch(mode.matches(&ty("enum{A=2'd0,B=2'd2}mode_t"))),
+            "enum variant `B` value 1 vs 2"
+        );
+    }
+
+    #[test]
+    fn test_unpacked_arrays() {
+        let pkts = ty("struct packed{logic[7:0] len;}pkt_t$[0:3]");
+        assert_eq!(
+            pkts.relation(&ty("struct packed{logic[7:0] len;}pkt_t$[4:1]")),
+            Ok(TypeRelation::Equivalent)
+        );
+
+        let array = ty("logic[7:0]$[0:3]");
+        assert!(
+            array
+                .assignment_compatible(&ty("logic[7:0]$[4:1]"))
+                .unwrap_err()
+                .is_undecided()
+        );
+        assert_eq!(
+            mismatch(array.assignment_compatible(&ty("logic[7:0]$[0:1][0:1]"))),
+            "unpacked dims differ: [0:3] vs [0:1][0:1]"
+        );
+        assert_eq!(
+            mismatch(array.assignment_compatible(&ty("logic[3:0]$[0:3]"))),
+            "width 8 vs 4"
+        );
+        assert_eq!(
+            array.relation(&ty("logic[31:0]")),
+            Ok(TypeRelation::CastCompatible)
+        );
+        assert_eq!(
+            mismatch(ty("union{logic a;}u_t").cast_compatible(&ty("logic"))),
+            "union `u_t` vs logic"
+        );
+    }
+}
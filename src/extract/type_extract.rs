@@ -5,60 +5,161 @@ use num_traits::Zero;
 
 use pest::Parser;
 use pest_derive::Parser;
+use std::collections::HashMap;
 use std::error::Error;
 
 #[derive(Parser)]
 #[grammar = "extract/grammar.pest"]
 struct DataTypeParser;
 
-#[derive(Debug, PartialEq)]
+/// A data type. Two types are equal if they have the same shape and names;
+/// the typedefs they were referenced through, see [`Type::aliases`], are not
+/// compared.
+#[derive(Debug, Clone)]
 pub enum Type {
     Logic {
         signed: bool,
         packed_dimensions: Vec<Range>,
         unpacked_dimensions: Vec<Range>,
+        aliases: Vec<String>,
     },
     Struct {
         name: String,
+        packed: bool,
         fields: Vec<Field>,
         packed_dimensions: Vec<Range>,
         unpacked_dimensions: Vec<Range>,
+        aliases: Vec<String>,
     },
     Union {
         name: String,
+        packed: bool,
         fields: Vec<Field>,
         packed_dimensions: Vec<Range>,
         unpacked_dimensions: Vec<Range>,
+        aliases: Vec<String>,
     },
     Enum {
         name: String,
         variants: Vec<Variant>,
         packed_dimensions: Vec<Range>,
         unpacked_dimensions: Vec<Range>,
+        aliases: Vec<String>,
     },
 }
 
-#[derive(Debug, PartialEq)]
+impl PartialEq for Type {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Type::Logic {
+                    signed,
+                    packed_dimensions,
+                    unpacked_dimensions,
+                    ..
+                },
+                Type::Logic {
+                    signed: other_signed,
+                    packed_dimensions: other_packed,
+                    unpacked_dimensions: other_unpacked,
+                    ..
+                },
+            ) => {
+                signed == other_signed
+                    && packed_dimensions == other_packed
+                    && unpacked_dimensions == other_unpacked
+            }
+            (
+                Type::Struct {
+                    name,
+                    packed,
+                    fields,
+                    packed_dimensions,
+                    unpacked_dimensions,
+                    ..
+                },
+                Type::Struct {
+                    name: other_name,
+                    packed: other_packed_kind,
+                    fields: other_fields,
+                    packed_dimensions: other_packed,
+                    unpacked_dimensions: other_unpacked,
+                    ..
+                },
+            )
+            | (
+                Type::Union {
+                    name,
+                    packed,
+                    fields,
+                    packed_dimensions,
+                    unpacked_dimensions,
+                    ..
+                },
+                Type::Union {
+                    name: other_name,
+                    packed: other_packed_kind,
+                    fields: other_fields,
+                    packed_dimensions: other_packed,
+                    unpacked_dimensions: other_unpacked,
+                    ..
+                },
+            ) => {
+                name == other_name
+                    && packed == other_packed_kind
+                    && fields == other_fields
+                    && packed_dimensions == other_packed
+                    && unpacked_dimensions == other_unpacked
+            }
+            (
+                Type::Enum {
+                    name,
+                    variants,
+                    packed_dimensions,
+                    unpacked_dimensions,
+                    ..
+                },
+                Type::Enum {
+                    name: other_name,
+                    variants: other_variants,
+                    packed_dimensions: other_packed,
+                    unpacked_dimensions: other_unpacked,
+                    ..
+                },
+            ) => {
+                name == other_name
+                    && variants == other_variants
+                    && packed_dimensions == other_packed
+                    && unpacked_dimensions == other_unpacked
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct Field {
     pub name: String,
     pub ty: Type,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Range {
     pub msb: i32,
     pub lsb: i32,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Variant {
     pub name: String,
     pub width: usize,
+    pub signed: bool,
     pub value: BigInt,
 }
 
 impl Type {
-    fn unpacked_dimensions(&self) -> &Vec<Range> {
+    /// Returns the unpacked dimensions, outermost first.
+    pub fn unpacked_dimensions(&self) -> &Vec<Range> {
         match self {
             Type::Logic {
                 unpacked_dimensions,
@@ -79,7 +180,8 @@ impl Type {
         }
     }
 
-    fn packed_dimensions(&self) -> &Vec<Range> {
+    /// Returns the packed dimensions, outermost first.
+    pub fn packed_dimensions(&self) -> &Vec<Range> {
         match self {
             Type::Logic {
                 packed_dimensions, ..
@@ -96,6 +198,61 @@ impl Type {
         }
     }
 
+    /// Returns the typedef names this type was referenced through, outermost
+    /// first and each qualified as written where it is used. For a port of
+    /// type `pkg::paddr_t`, where `pkg` declares `typedef addr_t paddr_t`,
+    /// this is `["pkg::paddr_t", "addr_t"]`. A typedef is only recorded when
+    /// it names the whole type, not when dimensions were added to it at the
+    /// use site. Aliases are not compared by `==`.
+    pub fn aliases(&self) -> &Vec<String> {
+        match self {
+            Type::Logic { aliases, .. }
+            | Type::Struct { aliases, .. }
+            | Type::Union { aliases, .. }
+            | Type::Enum { aliases, .. } => aliases,
+        }
+    }
+
+    /// Returns the outermost typedef name of this type, if it has one.
+    pub fn typedef_name(&self) -> Option<&str> {
+        self.aliases().first().map(String::as_str)
+    }
+
+    pub(crate) fn aliases_mut(&mut self) -> &mut Vec<String> {
+        match self {
+            Type::Logic { aliases, .. }
+            | Type::Struct { aliases, .. }
+            | Type::Union { aliases, .. }
+            | Type::Enum { aliases, .. } => aliases,
+        }
+    }
+
+    /// Returns mutable references to the packed and unpacked dimensions.
+    pub(crate) fn dimensions_mut(&mut self) -> (&mut Vec<Range>, &mut Vec<Range>) {
+        match self {
+            Type::Logic {
+                packed_dimensions,
+                unpacked_dimensions,
+                ..
+            }
+            | Type::Struct {
+                packed_dimensions,
+                unpacked_dimensions,
+                ..
+            }
+            | Type::Union {
+                packed_dimensions,
+                unpacked_dimensions,
+                ..
+            }
+            | Type::Enum {
+                packed_dimensions,
+                unpacked_dimensions,
+                ..
+            } => (packed_dimensions, unpacked_dimensions),
+        }
+    }
+
     fn number_of_elements(&self) -> Result<usize, &str> {
         if !self.unpacked_dimensions().is_empty() {
             return Err("Unpacked dimensions are not supported in width calculations");
@@ -140,26 +297,89 @@ impl Type {
             }
         }
     }
+
+    /// Returns the bit offset of each field within one element of a struct or
+    /// union, in declaration order.
+    ///
+    /// As in a packed struct, the first field occupies the most significant
+    /// bits and the last field starts at offset zero. All union fields start at
+    /// offset zero. Types without fields return an empty list.
+    pub fn field_offsets(&self) -> Result<Vec<usize>, &str> {
+        match self {
+            Type::Struct { fields, .. } => {
+                let mut offsets = vec![0; fields.len()];
+                let mut offset = 0;
+                for (index, field) in fields.iter().enumerate().rev() {
+                    offsets[index] = offset;
+                    offset += field.ty.width()?;
+                }
+                Ok(offsets)
+            }
+            Type::Union { fields, .. } => Ok(vec![0; fields.len()]),
+            Type::Logic { .. } | Type::Enum { .. } => Ok(Vec::new()),
+        }
+    }
 }
 
 /// Parses a slang type definition (from --ast-json) into a `Type`
 pub fn parse_type_definition(input: &str) -> Result<Type, Box<dyn Error>> {
+    parse_with_aliases(input, &HashMap::new())
+}
+
+/// Parses type text rendered by the `TypeResolver`, in which `@(name)`
+/// markers ahead of a type record the typedefs it was referenced through.
+/// The markers are removed before parsing and their names attached to the
+/// types they precede, so they are not part of the public grammar.
+pub(crate) fn parse_resolved_type(input: &str) -> Result<Type, Box<dyn Error>> {
+    let mut text = String::with_capacity(input.len());
+    let mut aliases: HashMap<usize, Vec<String>> = HashMap::new();
+    let mut rest = input;
+    while let Some(start) = rest.find("@(") {
+        text.push_str(&rest[..start]);
+        let marker = &rest[start + 2..];
+        let close = marker.find(')').ok_or("Unterminated typedef marker")?;
+        aliases
+            .entry(text.len())
+            .or_default()
+            .push(marker[..close].to_string());
+        rest = &marker[close + 1..];
+    }
+    text.push_str(rest);
+    parse_with_aliases(&text, &aliases)
+}
+
+/// Parses a type, giving the type that starts at each offset of `input` the
+/// typedef names recorded for that offset in `aliases`.
+fn parse_with_aliases(
+    input: &str,
+    aliases: &HashMap<usize, Vec<String>>,
+) -> Result<Type, Box<dyn Error>> {
     let mut parse_tree = DataTypeParser::parse(Rule::top, input)?;
     let ty = parse_tree.next().unwrap().into_inner().next().unwrap();
-    Ok(build_field_type(ty))
+    Ok(build_field_type(ty, aliases))
 }
 
-fn build_field_type(pair: pest::iterators::Pair<Rule>) -> Type {
-    let inner_pair = pair.into_inner().next().unwrap();
-    match inner_pair.as_rule() {
-        Rule::logic_type => build_logic_type(inner_pair, None, false),
-        Rule::struct_type => build_struct_or_union_type(inner_pair, false),
-        Rule::union_type => build_struct_or_union_type(inner_pair, true),
-        Rule::enum_type => build_enum_type(inner_pair),
-        Rule::int_type => build_logic_type(inner_pair, Some(Range { msb: 31, lsb: 0 }), true),
-        Rule::longint_type => build_logic_type(inner_pair, Some(Range { msb: 63, lsb: 0 }), true),
-        _ => unreachable!(),
+fn build_field_type(
+    pair: pest::iterators::Pair<Rule>,
+    aliases: &HashMap<usize, Vec<String>>,
+) -> Type {
+    let start = pair.as_span().start();
+    if let Some(inner_pair) = pair.into_inner().next() {
+        let mut ty = match inner_pair.as_rule() {
+            Rule::logic_type => build_logic_type(inner_pair, None, false),
+            Rule::struct_type => build_struct_or_union_type(inner_pair, false, aliases),
+            Rule::union_type => build_struct_or_union_type(inner_pair, true, aliases),
+            Rule::enum_type => build_enum_type(inner_pair),
+            Rule::int_type => build_logic_type(inner_pair, Some(Range { msb: 31, lsb: 0 }), true),
+            Rule::longint_type => {
+                build_logic_type(inner_pair, Some(Range { msb: 63, lsb: 0 }), true)
+            }
+            _ => unreachable!(),
+        };
+        *ty.aliases_mut() = aliases.get(&start).cloned().unwrap_or_default();
+        return ty;
     }
+    unreachable!()
 }
 
 fn build_logic_type(
@@ -212,22 +432,29 @@ fn build_logic_type(
         signed,
         packed_dimensions,
         unpacked_dimensions,
+        aliases: Vec::new(),
     }
 }
 
-fn build_struct_or_union_type(pair: pest::iterators::Pair<Rule>, is_union: bool) -> Type {
+fn build_struct_or_union_type(
+    pair: pest::iterators::Pair<Rule>,
+    is_union: bool,
+    aliases: &HashMap<usize, Vec<String>>,
+) -> Type {
     let inner = pair.into_inner();
     let mut fields = Vec::new();
     let mut packed_dimensions = Vec::new();
     let mut unpacked_dimensions = Vec::new();
     let mut name = String::new();
+    let mut packed = false;
 
     for inner_pair in inner {
         match inner_pair.as_rule() {
+            Rule::packed_keyword => packed = true,
             Rule::field_list => {
                 for field_pair in inner_pair.into_inner() {
                     if field_pair.as_rule() == Rule::field {
-                        let field = build_field(field_pair);
+                        let field = build_field(field_pair, aliases);
                         fields.push(field);
                     }
                 }
@@ -262,16 +489,20 @@ fn build_struct_or_union_type(pair: pest::iterators::Pair<Rule>, is_union: bool)
     if is_union {
         Type::Union {
             name,
+            packed,
             fields,
             packed_dimensions,
             unpacked_dimensions,
+            aliases: Vec::new(),
         }
     } else {
         Type::Struct {
             name,
+            packed,
             fields,
             packed_dimensions,
             unpacked_dimensions,
+            aliases: Vec::new(),
         }
     }
 }
@@ -326,13 +557,14 @@ fn build_enum_type(pair: pest::iterators::Pair<Rule>) -> Type {
         variants,
         packed_dimensions,
         unpacked_dimensions,
+        aliases: Vec::new(),
     }
 }
 
-fn build_field(pair: pest::iterators::Pair<Rule>) -> Field {
+fn build_field(pair: pest::iterators::Pair<Rule>, aliases: &HashMap<usize, Vec<String>>) -> Field {
     let mut inner = pair.into_inner();
     let field_type_pair = inner.next().unwrap();
-    let ty = build_field_type(field_type_pair);
+    let ty = build_field_type(field_type_pair, aliases);
     let name = inner.next().unwrap().as_str();
     Field {
         name: String::from(name).clone(),
@@ -356,7 +588,14 @@ fn build_variant(pair: pest::iterators::Pair<Rule>) -> Variant {
 
     let width = width.as_str().parse::<usize>().unwrap();
 
-    let magnitude_str = inner_inner.next().unwrap().as_str();
+    let mut magnitude = inner_inner.next().unwrap();
+    let mut signed = false;
+    if magnitude.as_rule() == Rule::signed_base {
+        signed = true;
+        magnitude = inner_inner.next().unwrap();
+    }
+
+    let magnitude_str = magnitude.as_str();
     let magnitude = BigUint::parse_bytes(magnitude_str.as_bytes(), 10).unwrap();
 
     let sign = if magnitude.is_zero() {
@@ -370,6 +609,7 @@ fn build_variant(pair: pest::iterators::Pair<Rule>) -> Variant {
     Variant {
         name,
         width,
+        signed,
         value: BigInt::from_biguint(sign, magnitude),
     }
 }
@@ -396,21 +636,25 @@ mod tests {
                     Variant {
                         name: "a".to_string(),
                         width: 42,
+                        signed: false,
                         value: BigInt::from(1),
                     },
                     Variant {
                         name: "b".to_string(),
                         width: 42,
+                        signed: true,
                         value: BigInt::from(-2),
                     },
                     Variant {
                         name: "c".to_string(),
                         width: 42,
+                        signed: false,
                         value: BigInt::from(3),
                     },
                 ],
                 packed_dimensions: vec![],
                 unpacked_dimensions: vec![],
+                aliases: vec![],
             }
         );
     }
@@ -424,6 +668,7 @@ mod tests {
                 signed: true,
                 packed_dimensions: vec![Range { msb: 31, lsb: 0 }],
                 unpacked_dimensions: vec![],
+                aliases: vec![],
             }
         );
     }
@@ -437,6 +682,7 @@ mod tests {
                 signed: false,
                 packed_dimensions: vec![Range { msb: 31, lsb: 0 }],
                 unpacked_dimensions: vec![],
+                aliases: vec![],
             }
         );
     }
@@ -450,6 +696,7 @@ mod tests {
                 signed: true,
                 packed_dimensions: vec![Range { msb: 63, lsb: 0 }],
                 unpacked_dimensions: vec![],
+                aliases: vec![],
             }
         );
     }
@@ -463,7 +710,19 @@ mod tests {
                 signed: false,
                 packed_dimensions: vec![Range { msb: 63, lsb: 0 }],
                 unpacked_dimensions: vec![],
+                aliases: vec![],
             }
         );
     }
+
+    #[test]
+    fn test_field_offsets() {
+        let type_def =
+            parse_type_definition("struct packed{logic[7:0] a;logic[1:0][2:0] b;logic c;}s_t")
+                .unwrap();
+        assert_eq!(type_def.field_offsets().unwrap(), vec![7, 1, 0]);
+
+        let type_def = parse_type_definition("union packed{logic[7:0] a;logic b;}u_t").unwrap();
+        assert_eq!(type_def.field_offsets().unwrap(), vec![0, 0]);
+    }
 }
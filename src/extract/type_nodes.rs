@@ -0,0 +1,305 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Construction of [`Type`] directly from Slang's structured type nodes.
+//!
+//! When a type link refers to a `TypeAlias`, `EnumType` or struct or union
+//! node, the `Type` is assembled from that node's JSON fields rather than from
+//! re-rendered text, so it does not depend on Slang's textual type syntax.
+//! Anything else, including alias targets that are only available as text,
+//! goes through the grammar as before. Both paths produce the same `Type`.
+
+use super::type_extract::{Field, Range, Type, Variant};
+use super::{TypeResolver, dimension_start, parse_integer_literal, parse_type_definition_no_error};
+use serde_json::Value;
+use std::error::Error;
+
+impl<'a> TypeResolver<'a> {
+    /// Builds the `Type` for a `type` field, from structured nodes where the
+    /// field links to one and from the textual grammar otherwise. A linked
+    /// node that cannot be built is an error; the grammar is not tried.
+    pub(crate) fn build_type(&self, type_text: &str) -> Result<Type, Box<dyn Error>> {
+        match self.build_linked(type_text) {
+            Some(built) => built,
+            None => parse_type_definition_no_error(&self.resolve(type_text)),
+        }
+    }
+
+    /// Builds a type from structured nodes if `type_text` is exactly one link
+    /// to a supported node, returning `None` otherwise.
+    fn build_linked(&self, type_text: &str) -> Option<Result<Type, Box<dyn Error>>> {
+        let captures = self.link_pattern.captures(type_text)?;
+        if captures[0].len() != type_text.len() {
+            return None;
+        }
+        let address = captures["addr"].parse::<u64>().ok()?;
+        let node = *self.by_address.get(&address)?;
+        self.build_node(node, &captures["name"])
+    }
+
+    /// Builds the type of one node as used under `display_name`, which carries
+    /// the use-site name and any dimensions applied there.
+    fn build_node(&self, node: &Value, display_name: &str) -> Option<Result<Type, Box<dyn Error>>> {
+        let (name, dimensions) = match dimension_start(display_name) {
+            Some(index) => display_name.split_at(index),
+            None => (display_name, ""),
+        };
+        let kind = node.get("kind")?.as_str()?;
+        let built = match kind {
+            "TypeAlias" => {
+                let target = node.get("target")?.as_str()?;
+                self.build_type(target).map(|mut ty| {
+                    rename(&mut ty, name);
+                    ty
+                })
+            }
+            "EnumType" => build_enum(node, name),
+            "PackedStructType" | "UnpackedStructType" | "PackedUnionType" | "UnpackedUnionType" => {
+                self.build_aggregate(node, kind, name)
+            }
+            _ => return None,
+        };
+        Some(built.and_then(|mut ty| {
+            let (packed, unpacked) = parse_dimensions(dimensions)?;
+            let has_dimensions = !packed.is_empty() || !unpacked.is_empty();
+            let (type_packed, type_unpacked) = ty.dimensions_mut();
+            // As in the textual path, a typedef's own dimensions precede those
+            // applied at the use site.
+            type_packed.extend(packed);
+            type_unpacked.extend(unpacked);
+            if has_dimensions {
+                ty.aliases_mut().clear();
+            } else if kind == "TypeAlias" {
+                ty.aliases_mut().insert(0, name.to_string());
+            }
+            Ok(ty)
+        }))
+    }
+
+    fn build_aggregate(
+        &self,
+        node: &Value,
+        kind: &str,
+        name: &str,
+    ) -> Result<Type, Box<dyn Error>> {
+        let mut fields = Vec::new();
+        let mut bit_offsets = Vec::new();
+        for member in node["members"]
+            .as_array()
+            .ok_or("Type node without members")?
+        {
+            if member["kind"].as_str() != Some("Field") {
+                continue;
+            }
+            let field_name = member["name"].as_str().ok_or("Field without a name")?;
+            let field_type = member["type"].as_str().ok_or("Field without a type")?;
+            fields.push(Field {
+                name: field_name.to_string(),
+                ty: self.build_type(field_type)?,
+            });
+            bit_offsets.push(member["bitOffset"].as_u64());
+        }
+
+        let packed = kind.starts_with("Packed");
+        let ty = if kind.ends_with("StructType") {
+            Type::Struct {
+                name: name.to_string(),
+                packed,
+                fields,
+                packed_dimensions: Vec::new(),
+                unpacked_dimensions: Vec::new(),
+                aliases: Vec::new(),
+            }
+        } else {
+            Type::Union {
+                name: name.to_string(),
+                packed,
+                fields,
+                packed_dimensions: Vec::new(),
+                unpacked_dimensions: Vec::new(),
+                aliases: Vec::new(),
+            }
+        };
+
+        // Slang's field offsets must agree with the layout this crate assumes.
+        if packed {
+            for (offset, bit_offset) in ty.field_offsets()?.iter().zip(bit_offsets) {
+                if bit_offset.is_some_and(|bit_offset| bit_offset != *offset as u64) {
+                    Err(format!("Unexpected field layout in `{name}`"))?;
+                }
+            }
+        }
+        Ok(ty)
+    }
+}
+
+fn build_enum(node: &Value, name: &str) -> Result<Type, Box<dyn Error>> {
+    let mut variants = Vec::new();
+    for member in node["members"]
+        .as_array()
+        .ok_or("Type node without members")?
+    {
+        if member["kind"].as_str() != Some("EnumValue") {
+            continue;
+        }
+        let variant_name = member["name"].as_str().ok_or("Enum value without a name")?;
+        let value = member["value"]
+            .as_str()
+            .ok_or("Enum value without a value")?;
+        let (width, signed, value) =
+            parse_integer_literal(value).ok_or_else(|| format!("Invalid enum value: {value}"))?;
+        variants.push(Variant {
+            name: variant_name.to_string(),
+            width,
+            signed,
+            value,
+        });
+    }
+    Ok(Type::Enum {
+        name: name.to_string(),
+        variants,
+        packed_dimensions: Vec::new(),
+        unpacked_dimensions: Vec::new(),
+        aliases: Vec::new(),
+    })
+}
+
+/// Gives a struct, union or enum the public name of the typedef it is used
+/// through, replacing any generated name.
+fn rename(ty: &mut Type, name: &str) {
+    match ty {
+        Type::Struct { name: nominal, .. }
+        | Type::Union { name: nominal, .. }
+        | Type::Enum { name: nominal, .. } => *nominal = name.to_string(),
+        Type::Logic { .. } => {}
+    }
+}
+
+/// Parses dimension text such as `[3:0]$[0:1]` into packed and unpacked
+/// ranges.
+fn parse_dimensions(dimensions: &str) -> Result<(Vec<Range>, Vec<Range>), Box<dyn Error>> {
+    if dimensions.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    let mut ty = parse_type_definition_no_error(&format!("logic{dimensions}"))?;
+    let (packed, unpacked) = ty.dimensions_mut();
+    Ok((std::mem::take(packed), std::mem::take(unpacked)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::parse_resolved_type;
+    use serde_json::json;
+
+    /// Builds `type_text` through both paths, checking that they agree.
+    fn build_both(resolver: &TypeResolver, type_text: &str) -> Type {
+        let structured = resolver
+            .build_linked(type_text)
+            .expect("expected a structured node")
+            .unwrap();
+        let textual = parse_resolved_type(&resolver.resolve(type_text)).unwrap();
+        assert_eq!(structured, textual, "paths disagree for `{type_text}`");
+        structured
+    }
+
+    #[test]
+    fn builds_structured_nodes() {
+        let ast = json!({
+            "nodes": [
+                {
+                    "kind": "PackedStructType",
+                    "name": "",
+                    "addr": 100,
+                    "members": [
+                        { "kind": "Field", "name": "len", "type": "logic[7:0]", "bitOffset": 3 },
+                        { "kind": "Field", "name": "mode", "type": "200 mode_t", "bitOffset": 1 },
+                        { "kind": "Field", "name": "last", "type": "logic", "bitOffset": 0 }
+                    ]
+                },
+                {
+                    "kind": "EnumType",
+                    "name": "mode_t",
+                    "addr": 200,
+                    "members": [
+                        { "kind": "EnumValue", "name": "IDLE", "value": "2'b0" },
+                        { "kind": "EnumValue", "name": "BUSY", "value": "2'b11" }
+                    ]
+                },
+                { "kind": "TypeAlias", "name": "hdr_t", "addr": 300, "target": "100 hdr_t" },
+                { "kind": "TypeAlias", "name": "word_t", "addr": 400, "target": "logic[31:0]" },
+                {
+                    "kind": "UnpackedUnionType",
+                    "name": "",
+                    "addr": 500,
+                    "members": [
+                        { "kind": "Field", "name": "hdr", "type": "300 pkg::hdr_t" },
+                        { "kind": "Field", "name": "words", "type": "400 word_t$[0:1]" }
+                    ]
+                },
+                { "kind": "TypeAlias", "name": "msg_t", "addr": 600, "target": "500 msg_t" }
+            ]
+        });
+        let resolver = TypeResolver::new(&ast);
+
+        let hdr = build_both(&resolver, "300 pkg::hdr_t");
+        assert_eq!(hdr.aliases(), &["pkg::hdr_t"]);
+        assert_eq!(hdr.width().unwrap(), 11);
+        let Type::Struct {
+            name,
+            packed,
+            fields,
+            ..
+        } = &hdr
+        else {
+            panic!("expected a struct");
+        };
+        assert_eq!(name, "pkg::hdr_t");
+        assert!(packed);
+        assert_eq!(fields[1].ty.packed_dimensions().len(), 0);
+
+        let array = build_both(&resolver, "300 pkg::hdr_t[3:0]$[1:0]");
+        assert!(array.aliases().is_empty());
+        assert_eq!(array.packed_dimensions(), &[Range { msb: 3, lsb: 0 }]);
+
+        let msg = build_both(&resolver, "600 msg_t");
+        assert_eq!(msg.aliases(), &["msg_t"]);
+        assert!(matches!(msg, Type::Union { packed: false, .. }));
+
+        assert_eq!(build_both(&resolver, "400 word_t").aliases(), &["word_t"]);
+    }
+
+    #[test]
+    fn falls_back_to_grammar() {
+        let ast = json!({
+            "nodes": [
+                {
+                    "kind": "TypeAlias",
+                    "addr": 100,
+                    "target": "struct packed{logic[7:0] data;}generated$1"
+                },
+                {
+                    "kind": "PackedStructType",
+                    "addr": 200,
+                    "members": [
+                        { "kind": "Field", "name": "data", "type": "logic[7:0]", "bitOffset": 4 }
+                    ]
+                }
+            ]
+        });
+        let resolver = TypeResolver::new(&ast);
+
+        // Inline text has no structured node to build from.
+        assert!(resolver.build_linked("logic[3:0]").is_none());
+        assert!(resolver.build_linked("100 bus_t logic").is_none());
+        assert_eq!(
+            resolver.build_type("100 bus_t").unwrap(),
+            parse_resolved_type("@(bus_t)struct packed{logic[7:0] data;}bus_t").unwrap()
+        );
+
+        // A field layout that disagrees with this crate's is rejected by the
+        // structured path, and the grammar is not asked to paper over it.
+        assert!(resolver.build_linked("200 bad_t").unwrap().is_err());
+        assert!(resolver.build_type("200 bad_t").is_err());
+        assert!(resolver.build_type("<error>").is_err());
+    }
+}
@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Expansion of aggregate ports into scalar and vector leaf signals.
+//!
+//! Leaves are generated depth first, most significant first, with these
+//! naming conventions, where `__` stands for the configured separator:
+//!
+//! - A struct is replaced by its fields in declaration order, each named
+//!   `<port>__<field>`, recursively.
+//! - An unpacked dimension, or a packed dimension of a struct, union, enum or
+//!   multidimensional vector, is replaced by one element per index, in
+//!   declaration order from the left bound to the right bound, each named
+//!   `<port>__<index>`. Negative indices are written as `n<magnitude>`.
+//! - A packed union is never expanded, since its members overlap. It becomes
+//!   a single vector of its width.
+//! - Enums and one-dimensional vectors are leaves.
+//!
+//! Each leaf records the bit slice it occupies in the port's bit-stream
+//! representation (IEEE 1800-2017 section 11.4.14.1), in which the first
+//! struct field and the left-bound array element are the most significant.
+//! For packed ports this is the bit position within the port itself. Ports
+//! without a bit-stream representation, such as those containing an unpacked
+//! union, cannot be flattened.
+
+use crate::extract::{Port, PortDir, Range, Type, bit_stream_width};
+use std::error::Error;
+
+/// A scalar or vector signal produced by flattening a port.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FlatPort {
+    /// The generated name, e.g. `bus__hdr__len`.
+    pub name: String,
+    pub dir: PortDir,
+    pub width: usize,
+    /// Whether the leaf's own type is signed, i.e. the type that `select`
+    /// evaluates to, not the port as a whole: a signed field stays signed in
+    /// an unsigned packed struct or array. The elements of a multidimensional
+    /// vector take the signedness of the vector, which is the only one the
+    /// `Type` model records for it.
+    pub signed: bool,
+    /// A SystemVerilog expression selecting the leaf from the original port,
+    /// e.g. `bus.hdr.len` or `data[1][3]`.
+    pub select: String,
+    /// The bits of the original port that the leaf maps to.
+    pub slice: Range,
+}
+
+/// Flattens each port in turn, see [`flatten_port`].
+pub fn flatten_ports(ports: &[Port], separator: &str) -> Result<Vec<FlatPort>, Box<dyn Error>> {
+    let mut leaves = Vec::new();
+    for port in ports {
+        leaves.extend(flatten_port(port, separator)?);
+    }
+    Ok(leaves)
+}
+
+/// Expands `port` into leaf signals, joining generated names with
+/// `separator`. Ports that are already leaves are returned unchanged.
+pub fn flatten_port(port: &Port, separator: &str) -> Result<Vec<FlatPort>, Box<dyn Error>> {
+    let mut flattener = Flattener {
+        dir: port.dir,
+        separator,
+        leaves: Vec::new(),
+    };
+    flattener.flatten(&port.ty, port.name.clone(), port.name.clone(), 0)?;
+    Ok(flattener.leaves)
+}
+
+struct Flattener<'a> {
+    dir: PortDir,
+    separator: &'a str,
+    leaves: Vec<FlatPort>,
+}
+
+impl<'a> Flattener<'a> {
+    /// Flattens `ty`, whose least significant bit is at `lsb` within the port.
+    fn flatten(
+        &mut self,
+        ty: &Type,
+        name: String,
+        select: String,
+        lsb: usize,
+    ) -> Result<(), Box<dyn Error>> {
+        let splits_packed = match ty {
+            Type::Logic { .. } => ty.packed_dimensions().len() > 1,
+            _ => !ty.packed_dimensions().is_empty(),
+        };
+        if !ty.unpacked_dimensions().is_empty() || splits_packed {
+            let mut element = ty.clone();
+            let (packed, unpacked) = element.dimensions_mut();
+            let range = if unpacked.is_empty() {
+                packed.remove(0)
+            } else {
+                unpacked.remove(0)
+            };
+            let width = stream_width(&element)?;
+            let indices = indices(&range);
+            let count = indices.len();
+            for (position, index) in indices.into_iter().enumerate() {
+                self.flatten(
+                    &element,
+                    format!("{name}{}{}", self.separator, index_name(index)),
+                    format!("{select}[{index}]"),
+                    lsb + (count - 1 - position) * width,
+                )?;
+            }
+            return Ok(());
+        }
+
+        if let Type::Struct { fields, .. } = ty {
+            let mut offset = lsb + stream_width(ty)?;
+            for field in fields {
+                offset -= stream_width(&field.ty)?;
+                self.flatten(
+                    &field.ty,
+                    format!("{name}{}{}", self.separator, field.name),
+                    format!("{select}.{}", field.name),
+                    offset,
+                )?;
+            }
+            return Ok(());
+        }
+
+        let width = stream_width(ty)?;
+        self.leaves.push(FlatPort {
+            name,
+            dir: self.dir,
+            width,
+            signed: matches!(ty, Type::Logic { signed: true, .. }),
+            select,
+            slice: Range {
+                msb: (lsb + width) as i32 - 1,
+                lsb: lsb as i32,
+            },
+        });
+        Ok(())
+    }
+}
+
+/// Returns the number of bits in the bit-stream representation of `ty`.
+fn stream_width(ty: &Type) -> Result<usize, Box<dyn Error>> {
+    bit_stream_width(ty).ok_or_else(|| {
+        "Cannot flatten a type without a bit-stream representation, such as an unpacked union"
+            .into()
+    })
+}
+
+/// Lists the indices of a dimension from its left bound to its right bound.
+fn indices(range: &Range) -> Vec<i32> {
+    if range.msb >= range.lsb {
+        (range.lsb..=range.msb).rev().collect()
+    } else {
+        (range.msb..=range.lsb).collect()
+    }
+}
+
+fn index_name(index: i32) -> String {
+    if index < 0 {
+        format!("n{}", -index)
+    } else {
+        index.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::parse_type_definition;
+
+    fn port(dir: PortDir, name: &str, ty: &str) -> Port {
+        Port {
+            dir,
+            name: name.to_string(),
+            ty: parse_type_definition(ty).unwrap(),
+            kind: None,
+            default: None,
+            expression: None,
+            location: None,
+            attributes: vec![],
+        }
+    }
+
+    fn summary(leaves: &[FlatPort]) -> Vec<String> {
+        leaves
+            .iter()
+            .map(|leaf| {
+                format!(
+                    "{} {} [{}:{}] {}",
+                    leaf.name, leaf.width, leaf.slice.msb, leaf.slice.lsb, leaf.select
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_leaf_ports() {
+        let leaves = flatten_ports(
+            &[
+                port(PortDir::Input, "clk", "logic"),
+                port(PortDir::Output, "count", "logic signed[7:0]"),
+            ],
+            "__",
+        )
+        .unwrap();
+        assert_eq!(
+            leaves,
+            vec![
+                FlatPort {
+                    name: "clk".to_string(),
+                    dir: PortDir::Input,
+                    width: 1,
+                    signed: false,
+                    select: "clk".to_string(),
+                    slice: Range { msb: 0, lsb: 0 },
+                },
+                FlatPort {
+                    name: "count".to_string(),
+                    dir: PortDir::Output,
+                    width: 8,
+                    signed: true,
+                    select: "count".to_string(),
+                    slice: Range { msb: 7, lsb: 0 },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_structs_and_unions() {
+        let leaves = flatten_port(
+            &port(
+                PortDir::Input,
+                "bus",
+                "struct packed{struct packed{logic[3:0] len;enum{A=2'd0,B=2'd1}mode_t mode;}hdr_t hdr;\
+                 union packed{logic[5:0] raw;logic[5:0] cooked;}data_t data;}bus_t",
+            ),
+            "__",
+        )
+        .unwrap();
+        assert_eq!(
+            summary(&leaves),
+            vec![
+                "bus__hdr__len 4 [11:8] bus.hdr.len",
+                "bus__hdr__mode 2 [7:6] bus.hdr.mode",
+                "bus__data 6 [5:0] bus.data",
+            ]
+        );
+        assert!(leaves.iter().all(|leaf| leaf.dir == PortDir::Input));
+    }
+
+    #[test]
+    fn test_arrays() {
+        let leaves =
+            flatten_port(&port(PortDir::Output, "data", "logic[1:0][3:0]$[0:1]"), "_").unwrap();
+        assert_eq!(
+            summary(&leaves),
+            vec![
+                "data_0_1 4 [15:12] data[0][1]",
+                "data_0_0 4 [11:8] data[0][0]",
+                "data_1_1 4 [7:4] data[1][1]",
+                "data_1_0 4 [3:0] data[1][0]",
+            ]
+        );
+
+        let leaves = flatten_port(
+            &port(
+                PortDir::Output,
+                "pkts",
+                "struct{logic[7:0] len;logic valid;}pkt_t$[-1:0]",
+            ),
+            ".",
+        )
+        .unwrap();
+        assert_eq!(
+            summary(&leaves),
+            vec![
+                "pkts.n1.len 8 [17:10] pkts[-1].len",
+                "pkts.n1.valid 1 [9:9] pkts[-1].valid",
+                "pkts.0.len 8 [8:1] pkts[0].len",
+                "pkts.0.valid 1 [0:0] pkts[0].valid",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_signedness() {
+        let leaves = flatten_port(
+            &port(
+                PortDir::Input,
+                "pairs",
+                "struct packed{logic signed[7:0] a;logic[7:0] b;}pair_t[1:0]",
+            ),
+            "_",
+        )
+        .unwrap();
+        let signed: Vec<(&str, bool)> = leaves
+            .iter()
+            .map(|leaf| (leaf.name.as_str(), leaf.signed))
+            .collect();
+        assert_eq!(
+            signed,
+            vec![
+                ("pairs_1_a", true),
+                ("pairs_1_b", false),
+                ("pairs_0_a", true),
+                ("pairs_0_b", false),
+            ]
+        );
+
+        let leaves =
+            flatten_port(&port(PortDir::Input, "data", "logic signed[1:0][3:0]"), "_").unwrap();
+        assert!(leaves.iter().all(|leaf| leaf.signed));
+    }
+
+    #[test]
+    fn test_unpacked_unions() {
+        let variant = port(
+            PortDir::Input,
+            "msg",
+            "struct{logic valid;union{logic[7:0] byte_;logic[31:0] word;}payload_t payload;}msg_t",
+        );
+        assert!(flatten_port(&variant, "__").is_err());
+    }
+}
@@ -1,267 +1,946 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::attributes::{Attribute, extract_attributes};
+use crate::connections::{PortConnection, SourceTexts, extract_connections, read_styles};
+use crate::expression::link_name;
+use crate::extract::{
+    MemberIter, ParameterDef, TypeResolver, body_parameter_defs, parse_integer_literal,
+};
+use crate::location::{SourceLocation, source_location};
+use num_traits::ToPrimitive;
 use serde_json::Value;
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::error::Error;
+use std::ops::Index;
 use std::rc::Rc;
 
+/// An instance and, recursively, the instances below it. See [`Hierarchy`]
+/// for a form with parent links and path lookup.
 #[derive(Debug, PartialEq)]
 pub struct Instance {
     pub def_name: String,
     pub inst_name: String,
     pub hier_prefix: String,
+    pub kind: InstanceKind,
+    /// The port connections of the instance, in port order.
+    pub connections: Vec<PortConnection>,
+    /// The elaborated value and type parameters of the instance.
+    pub parameters: Vec<ParameterDef>,
+    /// The parameters whose types the grammar does not support.
+    pub unsupported_parameters: Vec<UnsupportedParameter>,
     pub contents: Vec<Rc<RefCell<Instance>>>,
+    pub location: Option<SourceLocation>,
+    pub attributes: Vec<Attribute>,
+}
+
+/// A parameter whose type the grammar does not support, such as an unpacked
+/// array of `real`, kept as Slang prints it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UnsupportedParameter {
+    pub name: String,
+    /// The parameter's type, or for a type parameter the type it stands for.
+    pub ty: String,
+    /// The elaborated value, if it has one.
+    pub value: Option<String>,
+}
+
+/// What an instance in the hierarchy instantiates.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum InstanceKind {
+    Module,
+    Interface,
+    Program,
+    Checker,
+    /// A gate or user-defined primitive. Its `def_name` is the primitive's
+    /// name, e.g. `and`.
+    Primitive,
+    /// A definition that Slang did not elaborate, such as an unknown module
+    /// under `SlangConfig::ignore_unknown_modules`. Its contents are unknown.
+    Uninstantiated,
+}
+
+/// Identifies an instance within a [`Hierarchy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InstanceId(usize);
+
+impl InstanceId {
+    /// The position of the instance in depth-first creation order, for use
+    /// as an index into side tables.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// Identifies a generate block within a [`Hierarchy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GenerateBlockId(usize);
+
+impl GenerateBlockId {
+    /// The position of the block in creation order, for use as an index into
+    /// side tables.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// An entry of an instance body or generate block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeMember {
+    Instance(InstanceId),
+    GenerateBlock(GenerateBlockId),
+}
+
+/// The generate construct a block belongs to.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum GenerateConstruct {
+    /// A branch of an `if` or `case` generate. Branches are counted in source
+    /// order over the blocks of the construct, so the `else` block of an
+    /// `if (...) begin ... end else begin ... end` is branch 1.
+    Conditional { branch: usize },
+    /// One iteration of a `for` generate loop.
+    Loop {
+        /// The genvar of the loop, if Slang reports it.
+        variable: Option<String>,
+        /// The value of the genvar in this iteration, which is also the index
+        /// of the block in hierarchical paths, e.g. `g[3]`.
+        value: i64,
+    },
+}
+
+/// One generate block of a [`Hierarchy`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct GenerateBlock {
+    /// The name of the block, or of its loop for the iterations of a generate
+    /// loop.
+    pub name: String,
+    /// Whether the block is unnamed in the source, so that `name` is the one
+    /// assigned by the language, e.g. `genblk1`.
+    pub auto_named: bool,
+    pub construct: GenerateConstruct,
+    /// Whether the block is a branch that was not taken. Such blocks have no
+    /// members.
+    pub uninstantiated: bool,
+    pub location: Option<SourceLocation>,
+    instance: InstanceId,
+    parent: Option<GenerateBlockId>,
+    members: Vec<ScopeMember>,
+}
+
+impl GenerateBlock {
+    /// The instance whose body contains the block.
+    pub fn instance(&self) -> InstanceId {
+        self.instance
+    }
+
+    /// The generate block directly containing this one, if any.
+    pub fn parent(&self) -> Option<GenerateBlockId> {
+        self.parent
+    }
+
+    /// The instances and generate blocks directly inside the block, in
+    /// design order.
+    pub fn members(&self) -> &[ScopeMember] {
+        &self.members
+    }
+
+    /// The name of the block as it appears in hierarchical paths, e.g.
+    /// `genblk1` or `g[3]`.
+    pub fn path_name(&self) -> String {
+        match &self.construct {
+            GenerateConstruct::Loop { value, .. } => format!("{}[{value}]", self.name),
+            GenerateConstruct::Conditional { .. } => self.name.clone(),
+        }
+    }
+}
+
+/// One instance of a [`Hierarchy`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct HierarchyNode {
+    pub def_name: String,
+    pub inst_name: String,
+    /// The generate blocks between the parent instance and this one, e.g.
+    /// `.g[0].genblk1`.
+    pub hier_prefix: String,
+    pub kind: InstanceKind,
+    /// The port connections of the instance, in port order.
+    pub connections: Vec<PortConnection>,
+    /// The elaborated value and type parameters of the instance. Parameters
+    /// of types the grammar does not support are in `unsupported_parameters`
+    /// instead.
+    pub parameters: Vec<ParameterDef>,
+    pub unsupported_parameters: Vec<UnsupportedParameter>,
+    pub location: Option<SourceLocation>,
+    pub attributes: Vec<Attribute>,
+    parent: Option<InstanceId>,
+    generate_block: Option<GenerateBlockId>,
+    children: Vec<InstanceId>,
+    members: Vec<ScopeMember>,
+}
+
+/// Instances of one definition that share the same parameter values.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InstanceGroup {
+    pub def_name: String,
+    /// The parameters of the first instance of the group.
+    pub parameters: Vec<ParameterDef>,
+    pub unsupported_parameters: Vec<UnsupportedParameter>,
+    pub instances: Vec<InstanceId>,
+}
+
+/// The instance tree of a design, stored as a flat list of nodes that refer
+/// to each other by [`InstanceId`].
+///
+/// The generate blocks of each instance body are kept alongside, so that the
+/// parent of an instance is always an instance while its enclosing generate
+/// blocks remain available through [`Hierarchy::members`] and
+/// [`Hierarchy::generate_block`].
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Hierarchy {
+    nodes: Vec<HierarchyNode>,
+    generate_blocks: Vec<GenerateBlock>,
+    roots: Vec<InstanceId>,
+    paths: HashMap<String, InstanceId>,
+}
+
+impl Index<InstanceId> for Hierarchy {
+    type Output = HierarchyNode;
+
+    fn index(&self, id: InstanceId) -> &Self::Output {
+        &self.nodes[id.0]
+    }
+}
+
+impl Index<GenerateBlockId> for Hierarchy {
+    type Output = GenerateBlock;
+
+    fn index(&self, id: GenerateBlockId) -> &Self::Output {
+        &self.generate_blocks[id.0]
+    }
+}
+
+impl Hierarchy {
+    /// The number of instances in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The top-level instances, in design order.
+    pub fn roots(&self) -> &[InstanceId] {
+        &self.roots
+    }
+
+    pub fn parent(&self, id: InstanceId) -> Option<InstanceId> {
+        self[id].parent
+    }
+
+    /// The instances directly below `id`, in design order.
+    pub fn children(&self, id: InstanceId) -> &[InstanceId] {
+        &self[id].children
+    }
+
+    /// The instances and generate blocks directly inside the body of `id`, in
+    /// design order.
+    pub fn members(&self, id: InstanceId) -> &[ScopeMember] {
+        &self[id].members
+    }
+
+    /// The innermost generate block containing `id` within its parent's body.
+    pub fn generate_block(&self, id: InstanceId) -> Option<GenerateBlockId> {
+        self[id].generate_block
+    }
+
+    /// Iterates over all generate blocks, each before the blocks inside it.
+    /// Blocks of branches that were not taken are included and marked as
+    /// uninstantiated.
+    pub fn generate_blocks(&self) -> impl Iterator<Item = GenerateBlockId> + '_ {
+        (0..self.generate_blocks.len()).map(GenerateBlockId)
+    }
+
+    /// The full hierarchical path of a generate block, e.g.
+    /// `top.u_core.g[3]`.
+    pub fn generate_block_path(&self, id: GenerateBlockId) -> String {
+        let block = &self[id];
+        let scope = match block.parent {
+            Some(parent) => self.generate_block_path(parent),
+            None => self.path(block.instance),
+        };
+        format!("{scope}.{}", block.path_name())
+    }
+
+    /// The full hierarchical path of an instance, e.g.
+    /// `top.u_core.genblk1.u_alu`.
+    pub fn path(&self, id: InstanceId) -> String {
+        let node = &self[id];
+        match node.parent {
+            Some(parent) => format!(
+                "{}{}.{}",
+                self.path(parent),
+                node.hier_prefix,
+                node.inst_name
+            ),
+            None => node.inst_name.clone(),
+        }
+    }
+
+    /// Looks up an instance by its full hierarchical path.
+    pub fn find(&self, path: &str) -> Option<InstanceId> {
+        self.paths.get(path).copied()
+    }
+
+    /// Returns the instances whose paths match a glob-style pattern, in
+    /// depth-first order.
+    ///
+    /// The pattern is matched one path component at a time: `*` matches any
+    /// part of a component, `?` any one character, and a `**` component any
+    /// number of components. For example, `top.**.u_fifo*` matches every
+    /// instance below `top` whose name starts with `u_fifo`.
+    pub fn glob(&self, pattern: &str) -> Vec<InstanceId> {
+        let pattern: Vec<&str> = pattern.split('.').collect();
+        self.depth_first()
+            .filter(|&id| {
+                let path = self.path(id);
+                let components: Vec<&str> = path.split('.').collect();
+                glob_components(&pattern, &components)
+            })
+            .collect()
+    }
+
+    /// Iterates over all instances, each before its children.
+    pub fn depth_first(&self) -> impl Iterator<Item = InstanceId> + '_ {
+        let mut stack: Vec<InstanceId> = self.roots.iter().rev().copied().collect();
+        std::iter::from_fn(move || {
+            let id = stack.pop()?;
+            stack.extend(self[id].children.iter().rev());
+            Some(id)
+        })
+    }
+
+    /// Iterates over all instances level by level, starting with the roots.
+    pub fn breadth_first(&self) -> impl Iterator<Item = InstanceId> + '_ {
+        let mut queue: VecDeque<InstanceId> = self.roots.iter().copied().collect();
+        std::iter::from_fn(move || {
+            let id = queue.pop_front()?;
+            queue.extend(self[id].children.iter());
+            Some(id)
+        })
+    }
+
+    /// Groups the instances that use the same definition with the same
+    /// parameter values, in the depth-first order of each group's first
+    /// instance.
+    ///
+    /// Parameters are compared by name, type and value, so an instance that
+    /// overrides a parameter with its default value shares a group with those
+    /// that do not override it. Types are compared with `==`, which ignores
+    /// the typedefs they were spelled through, so that spelling a type
+    /// through another typedef does not split a group. Parameters of unsupported types are compared by name, type
+    /// and value as Slang prints them.
+    pub fn group_by_parameters(&self) -> Vec<InstanceGroup> {
+        let same_parameters = |a: &[ParameterDef], b: &[ParameterDef]| {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(a, b)| a.name == b.name && a.value == b.value && a.ty == b.ty)
+        };
+        let mut groups: Vec<InstanceGroup> = Vec::new();
+        for id in self.depth_first() {
+            let node = &self[id];
+            match groups.iter_mut().find(|group| {
+                group.def_name == node.def_name
+                    && same_parameters(&group.parameters, &node.parameters)
+                    && group.unsupported_parameters == node.unsupported_parameters
+            }) {
+                Some(group) => group.instances.push(id),
+                None => groups.push(InstanceGroup {
+                    def_name: node.def_name.clone(),
+                    parameters: node.parameters.clone(),
+                    unsupported_parameters: node.unsupported_parameters.clone(),
+                    instances: vec![id],
+                }),
+            }
+        }
+        groups
+    }
+
+    /// Converts the tree into the nested [`Instance`] form returned by
+    /// [`extract_hierarchy`], keyed by the definition name of each root.
+    pub fn to_instances(&self) -> HashMap<String, Instance> {
+        self.roots
+            .iter()
+            .map(|&root| (self[root].def_name.clone(), self.to_instance(root)))
+            .collect()
+    }
+
+    fn to_instance(&self, id: InstanceId) -> Instance {
+        let node = &self[id];
+        Instance {
+            def_name: node.def_name.clone(),
+            inst_name: node.inst_name.clone(),
+            hier_prefix: node.hier_prefix.clone(),
+            kind: node.kind,
+            connections: node.connections.clone(),
+            parameters: node.parameters.clone(),
+            unsupported_parameters: node.unsupported_parameters.clone(),
+            contents: node
+                .children
+                .iter()
+                .map(|&child| Rc::new(RefCell::new(self.to_instance(child))))
+                .collect(),
+            location: node.location.clone(),
+            attributes: node.attributes.clone(),
+        }
+    }
+
+    /// Reads how the port connections of each instance are written, e.g.
+    /// `.clk`, `.d(a)`, `.*` or by position, from the source files named by
+    /// the instances' locations, and sets [`PortConnection::style`].
+    ///
+    /// This needs the design to be extracted with
+    /// `SlangConfig::include_source_info`, and reads the files as they are on
+    /// disk now rather than as Slang parsed them. Styles are left unset where
+    /// the connection list cannot be found at the instance's location, e.g.
+    /// for instances written by a macro, and may be wrong if the files have
+    /// been edited since the extraction.
+    pub fn read_connection_styles(&mut self) {
+        let mut sources = SourceTexts::default();
+        for node in &mut self.nodes {
+            if let Some(location) = &node.location {
+                read_styles(&mut node.connections, location, &mut sources);
+            }
+        }
+    }
+
+    fn add(
+        &mut self,
+        parent: Option<InstanceId>,
+        generate_block: Option<GenerateBlockId>,
+        mut node: HierarchyNode,
+    ) -> InstanceId {
+        let id = InstanceId(self.nodes.len());
+        node.parent = parent;
+        node.generate_block = generate_block;
+        self.nodes.push(node);
+        match parent {
+            Some(parent) => {
+                self.nodes[parent.0].children.push(id);
+                self.scope_members(parent, generate_block)
+                    .push(ScopeMember::Instance(id));
+            }
+            None => self.roots.push(id),
+        }
+        self.paths.insert(self.path(id), id);
+        id
+    }
+
+    fn add_generate_block(&mut self, block: GenerateBlock) -> GenerateBlockId {
+        let id = GenerateBlockId(self.generate_blocks.len());
+        let (instance, parent) = (block.instance, block.parent);
+        self.generate_blocks.push(block);
+        self.scope_members(instance, parent)
+            .push(ScopeMember::GenerateBlock(id));
+        id
+    }
+
+    /// The members of the body of `instance`, or of a generate block in it.
+    fn scope_members(
+        &mut self,
+        instance: InstanceId,
+        generate_block: Option<GenerateBlockId>,
+    ) -> &mut Vec<ScopeMember> {
+        match generate_block {
+            Some(block) => &mut self.generate_blocks[block.0].members,
+            None => &mut self.nodes[instance.0].members,
+        }
+    }
+}
+
+fn glob_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| glob_components(rest, &path[skip..])),
+        Some((component, rest)) => match path.split_first() {
+            Some((first, path_rest)) => {
+                glob_component(component.as_bytes(), first.as_bytes())
+                    && glob_components(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+fn glob_component(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| glob_component(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && glob_component(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && glob_component(rest, &text[1..]),
+    }
 }
 
 pub fn extract_hierarchy(
     cfg: &crate::SlangConfig,
 ) -> Result<HashMap<String, Instance>, Box<dyn Error>> {
-    Ok(extract_hierarchy_from_value(&crate::run_slang(cfg)?))
+    Ok(hierarchy_tree(&crate::run_slang(cfg)?, cfg.parameters).to_instances())
 }
 
 pub fn extract_hierarchy_from_value(value: &Value) -> HashMap<String, Instance> {
-    let mut top_level_instances = HashMap::new();
-    let symbols = AstSymbols::new(value);
+    extract_hierarchy_tree_from_value(value).to_instances()
+}
+
+pub fn extract_hierarchy_tree(cfg: &crate::SlangConfig) -> Result<Hierarchy, Box<dyn Error>> {
+    Ok(hierarchy_tree(&crate::run_slang(cfg)?, cfg.parameters))
+}
+
+pub fn extract_hierarchy_tree_from_value(value: &Value) -> Hierarchy {
+    hierarchy_tree(value, &[])
+}
+
+/// Builds the instance tree, marking the parameters of top-level instances
+/// that are named in `overrides`.
+fn hierarchy_tree(value: &Value, overrides: &[(&str, &str)]) -> Hierarchy {
+    walk(value, overrides).hierarchy
+}
+
+/// Builds the instance tree along with the elaborated body of each instance,
+/// indexed by [`InstanceId::index`], for analyses that read the bodies.
+/// Primitives and uninstantiated definitions have no body.
+pub(crate) fn hierarchy_with_bodies(value: &Value) -> (Hierarchy, Vec<Option<&Value>>) {
+    let walker = walk(value, &[]);
+    (walker.hierarchy, walker.bodies)
+}
+
+fn walk<'a>(value: &'a Value, overrides: &[(&str, &str)]) -> Walker<'a> {
+    let mut walker = Walker {
+        hierarchy: Hierarchy::default(),
+        bodies: Vec::new(),
+        symbols: TypeResolver::new(value),
+        definition_kinds: definition_kinds(value),
+    };
 
     if let Some(members) = value
         .get("design")
         .and_then(|v| v.get("members").and_then(|v| v.as_array()))
     {
         for member in members {
-            let member = symbols.resolve(member);
+            let member = walker.symbols.resolve_node(member);
             if let Some(kind) = member.get("kind") {
                 if kind == "Instance" {
-                    if let Some((mut inst, value)) =
-                        descend_into_instance(member, "".to_string(), &symbols)
-                    {
-                        extract_hierarchy_from_value_helper(
-                            &mut inst,
-                            value,
-                            "".to_string(),
-                            &symbols,
-                        );
-                        top_level_instances.insert(inst.def_name.clone(), inst);
-                    }
+                    let inst_name = member["name"].as_str().unwrap_or_default();
+                    walker.instance(member, inst_name, None, None, "".to_string(), overrides);
                 }
             }
         }
     }
 
-    top_level_instances
+    walker
 }
 
-fn extract_hierarchy_from_value_helper(
-    top: &mut Instance,
-    value: &Value,
-    hier_prefix: String,
-    symbols: &AstSymbols,
-) {
-    let symbol_table = create_symbol_table(value);
-    if let Some(members) = value.get("members").and_then(|v| v.as_array()) {
-        for member in members {
-            let member = symbols.resolve(member);
-            if let Some(kind) = member.get("kind") {
-                if kind == "Instance" {
-                    if let Some((mut inst, value)) =
-                        descend_into_instance(member, hier_prefix.clone(), symbols)
-                    {
-                        extract_hierarchy_from_value_helper(
-                            &mut inst,
-                            value,
-                            "".to_string(),
-                            symbols,
+/// Reads the kind of each definition from the `definitions` list, for
+/// instance bodies that do not link to their definition.
+fn definition_kinds(value: &Value) -> HashMap<&str, InstanceKind> {
+    let mut kinds = HashMap::new();
+    if let Some(definitions) = value.get("definitions").and_then(|v| v.as_array()) {
+        for definition in definitions {
+            if let Some(name) = definition.get("name").and_then(|v| v.as_str()) {
+                if let Some(kind) = definition_kind(definition) {
+                    kinds.insert(name, kind);
+                }
+            }
+        }
+    }
+    kinds
+}
+
+fn definition_kind(definition: &Value) -> Option<InstanceKind> {
+    match definition.get("definitionKind")?.as_str()? {
+        "Module" => Some(InstanceKind::Module),
+        "Interface" => Some(InstanceKind::Interface),
+        "Program" => Some(InstanceKind::Program),
+        _ => None,
+    }
+}
+
+struct Walker<'a> {
+    hierarchy: Hierarchy,
+    bodies: Vec<Option<&'a Value>>,
+    symbols: TypeResolver<'a>,
+    definition_kinds: HashMap<&'a str, InstanceKind>,
+}
+
+impl<'a> Walker<'a> {
+    /// Adds an instance below `parent`, inside `generate_block` of the
+    /// parent's body, followed by the instances within it.
+    fn instance(
+        &mut self,
+        value: &'a Value,
+        inst_name: &str,
+        parent: Option<InstanceId>,
+        generate_block: Option<GenerateBlockId>,
+        hier_prefix: String,
+        overrides: &[(&str, &str)],
+    ) {
+        if let Some((node, body)) = self.describe_instance(value, inst_name, hier_prefix, overrides)
+        {
+            let id = self.hierarchy.add(parent, generate_block, node);
+            self.bodies.push(body);
+            if let Some(body) = body {
+                self.scope(id, None, body, "".to_string());
+            }
+        }
+    }
+
+    /// Visits the members of an instance body or generate block, adding the
+    /// instances and generate blocks found there below `top`.
+    fn scope(
+        &mut self,
+        top: InstanceId,
+        generate_block: Option<GenerateBlockId>,
+        value: &'a Value,
+        hier_prefix: String,
+    ) {
+        let symbol_table = create_symbol_table(value);
+        let mut branches: HashMap<i64, usize> = HashMap::new();
+        if let Some(members) = value.get("members").and_then(|v| v.as_array()) {
+            for member in members {
+                let member = self.symbols.resolve_node(member);
+                match member.get("kind").and_then(|v| v.as_str()) {
+                    Some("Instance")
+                    | Some("CheckerInstance")
+                    | Some("PrimitiveInstance")
+                    | Some("UninstantiatedDef") => {
+                        let inst_name = member["name"].as_str().unwrap_or_default();
+                        self.instance(
+                            member,
+                            inst_name,
+                            Some(top),
+                            generate_block,
+                            hier_prefix.clone(),
+                            &[],
                         );
-                        top.contents.push(Rc::new(RefCell::new(inst)));
                     }
-                } else if kind == "UninstantiatedDef" {
-                    if let Some(inst_name) = member.get("name").and_then(|v| v.as_str()) {
-                        if let Some(def_name) =
-                            member.get("definitionName").and_then(|v| v.as_str())
-                        {
-                            top.contents.push(Rc::new(RefCell::new(Instance {
-                                def_name: def_name.to_string(),
-                                inst_name: inst_name.to_string(),
-                                hier_prefix: hier_prefix.clone(),
-                                contents: Vec::new(),
-                            })));
-                        }
+                    Some("InstanceArray") => {
+                        let inst_name = member["name"].as_str().unwrap_or_default();
+                        self.instance_array(member, inst_name, top, generate_block, &hier_prefix);
                     }
-                } else if kind == "GenerateBlock" {
-                    if let Some((hier_prefix, value)) =
-                        descend_into_generate_block(member, hier_prefix.clone(), &symbol_table)
-                    {
-                        extract_hierarchy_from_value_helper(
-                            top,
-                            value,
-                            hier_prefix.clone(),
-                            symbols,
-                        );
+                    Some("GenerateBlock") => {
+                        let Some((name, auto_named)) = generate_block_name(member, &symbol_table)
+                        else {
+                            continue;
+                        };
+                        let construct_index = member["constructIndex"].as_i64().unwrap_or(-1);
+                        let branch = branches.entry(construct_index).or_default();
+                        let uninstantiated = member["isUninstantiated"].as_bool() == Some(true);
+                        let block = self.hierarchy.add_generate_block(GenerateBlock {
+                            name: name.clone(),
+                            auto_named,
+                            construct: GenerateConstruct::Conditional { branch: *branch },
+                            uninstantiated,
+                            location: source_location(member),
+                            instance: top,
+                            parent: generate_block,
+                            members: Vec::new(),
+                        });
+                        *branch += 1;
+                        if !uninstantiated {
+                            self.scope(top, Some(block), member, format!("{hier_prefix}.{name}"));
+                        }
                     }
-                } else if kind == "GenerateBlockArray" {
-                    if let Some(elements) = descend_into_generate_block_array(
-                        member,
-                        hier_prefix.clone(),
-                        &symbol_table,
-                    ) {
-                        for (hier_prefix, element) in elements {
-                            extract_hierarchy_from_value_helper(
+                    Some("GenerateBlockArray") => {
+                        let Some((name, auto_named)) = generate_block_name(member, &symbol_table)
+                        else {
+                            continue;
+                        };
+                        for element in generate_block_array_elements(member) {
+                            let Some(value) = generate_array_index(element) else {
+                                continue;
+                            };
+                            let block = self.hierarchy.add_generate_block(GenerateBlock {
+                                name: name.clone(),
+                                auto_named,
+                                construct: GenerateConstruct::Loop {
+                                    variable: loop_variable(element),
+                                    value,
+                                },
+                                uninstantiated: false,
+                                location: source_location(element),
+                                instance: top,
+                                parent: generate_block,
+                                members: Vec::new(),
+                            });
+                            self.scope(
                                 top,
+                                Some(block),
                                 element,
-                                hier_prefix.clone(),
-                                symbols,
+                                format!("{hier_prefix}.{name}[{value}]"),
                             );
                         }
                     }
+                    _ => {}
                 }
             }
         }
     }
-}
 
-fn descend_into_instance<'a>(
-    value: &'a Value,
-    hier_prefix: String,
-    symbols: &AstSymbols<'a>,
-) -> Option<(Instance, &'a Value)> {
-    if let Some(inst_name) = value.get("name").and_then(|v| v.as_str()) {
+    /// Adds the elements of an instance array, named by their index, e.g.
+    /// `u_bus[1]`. The elements of a multidimensional array are arrays
+    /// themselves.
+    fn instance_array(
+        &mut self,
+        value: &'a Value,
+        inst_name: &str,
+        parent: InstanceId,
+        generate_block: Option<GenerateBlockId>,
+        hier_prefix: &str,
+    ) {
         if inst_name.is_empty() {
-            return None;
+            return;
         }
-        if let Some(body) = value.get("body").map(|body| symbols.resolve(body)) {
-            if let Some(def_name) = body.get("name").and_then(|v| v.as_str()) {
-                if def_name.is_empty() {
-                    return None;
-                }
-                return Some((
-                    Instance {
-                        def_name: def_name.to_string(),
-                        inst_name: inst_name.to_string(),
-                        hier_prefix,
-                        contents: Vec::new(),
-                    },
-                    body,
-                ));
+        let elements = value["members"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let lower = array_lower_bound(value);
+        for (position, element) in elements.iter().enumerate() {
+            let element = self.symbols.resolve_node(element);
+            let element_name = format!("{inst_name}[{}]", lower + position as i64);
+            if element["kind"] == "InstanceArray" {
+                self.instance_array(element, &element_name, parent, generate_block, hier_prefix);
+            } else {
+                self.instance(
+                    element,
+                    &element_name,
+                    Some(parent),
+                    generate_block,
+                    hier_prefix.to_string(),
+                    &[],
+                );
             }
         }
     }
 
-    None
-}
+    /// Describes an instance and returns the body to descend into, if any.
+    fn describe_instance(
+        &mut self,
+        value: &'a Value,
+        inst_name: &str,
+        hier_prefix: String,
+        overrides: &[(&str, &str)],
+    ) -> Option<(HierarchyNode, Option<&'a Value>)> {
+        if inst_name.is_empty() {
+            return None;
+        }
+        let (kind, def_name, body) = match value.get("kind").and_then(|v| v.as_str())? {
+            "UninstantiatedDef" => (
+                InstanceKind::Uninstantiated,
+                value.get("definitionName").and_then(|v| v.as_str())?,
+                None,
+            ),
+            "PrimitiveInstance" => (
+                InstanceKind::Primitive,
+                link_name(&value["primitiveType"])?,
+                None,
+            ),
+            kind => {
+                let body = self.symbols.resolve_node(value.get("body")?);
+                let def_name = body.get("name").and_then(|v| v.as_str())?;
+                let kind = if kind == "CheckerInstance" {
+                    InstanceKind::Checker
+                } else {
+                    definition_kind(self.symbols.resolve_node(&body["definition"]))
+                        .or_else(|| self.definition_kinds.get(def_name).copied())
+                        .unwrap_or(InstanceKind::Module)
+                };
+                (kind, def_name, Some(body))
+            }
+        };
+        if def_name.is_empty() {
+            return None;
+        }
 
-/// Resolves Slang AST links. Slang v10 and newer serialize a node only once and
-/// represent subsequent references as strings containing the node address and
-/// name (for example, `"123456 C"`). Older releases serialize those nodes
-/// inline, so accepting both forms keeps this crate compatible across versions.
-struct AstSymbols<'a> {
-    /// Every serialized AST object that can be the target of an address link.
-    by_address: HashMap<u64, &'a Value>,
+        let location = source_location(value);
+        let (connections, parameters, unsupported_parameters) = match body {
+            Some(body) => {
+                let parameters = body_parameter_defs(&self.symbols, body, true, overrides);
+                let unsupported = unsupported_parameters(body, &parameters);
+                (
+                    extract_connections(value, |v| self.symbols.resolve_node(v)),
+                    parameters,
+                    unsupported,
+                )
+            }
+            None => (Vec::new(), Vec::new(), Vec::new()),
+        };
+        Some((
+            HierarchyNode {
+                def_name: def_name.to_string(),
+                inst_name: inst_name.to_string(),
+                hier_prefix,
+                kind,
+                connections,
+                parameters,
+                unsupported_parameters,
+                location,
+                attributes: extract_attributes(value),
+                parent: None,
+                generate_block: None,
+                children: Vec::new(),
+                members: Vec::new(),
+            },
+            body,
+        ))
+    }
 }
 
-impl<'a> AstSymbols<'a> {
-    /// Indexes the complete JSON document so links can cross AST scopes.
-    fn new(root: &'a Value) -> Self {
-        let mut by_address = HashMap::new();
-        Self::index(root, &mut by_address);
-        Self { by_address }
-    }
+/// The lowest index of an instance array, whose elements Slang lists in
+/// ascending index order whichever way its range is declared.
+fn array_lower_bound(array: &Value) -> i64 {
+    array["range"]
+        .as_str()
+        .and_then(|range| {
+            let (left, right) = range
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .split_once(':')?;
+            Some(
+                left.trim()
+                    .parse::<i64>()
+                    .ok()?
+                    .min(right.trim().parse().ok()?),
+            )
+        })
+        .unwrap_or(0)
+}
 
-    /// Recursively records each object with an `addr` field.
-    fn index(value: &'a Value, by_address: &mut HashMap<u64, &'a Value>) {
-        match value {
-            Value::Object(object) => {
-                if let Some(address) = object.get("addr").and_then(Value::as_u64) {
-                    by_address.insert(address, value);
-                }
-                for child in object.values() {
-                    Self::index(child, by_address);
-                }
-            }
-            Value::Array(array) => {
-                for child in array {
-                    Self::index(child, by_address);
-                }
+/// Lists the parameters of a body that `body_parameter_defs` skipped because
+/// the grammar does not support their types.
+fn unsupported_parameters(body: &Value, parameters: &[ParameterDef]) -> Vec<UnsupportedParameter> {
+    MemberIter::new(body, &["Parameter", "TypeParameter"])
+        .filter_map(|member| {
+            let name = member["name"].as_str()?;
+            if parameters.iter().any(|parameter| parameter.name == name) {
+                return None;
             }
-            _ => {}
-        }
-    }
-
-    /// Follows a v10+ string link to its serialized object.
-    ///
-    /// Inline objects from older Slang releases are returned unchanged. An
-    /// unknown or malformed link is also left untouched, allowing the caller's
-    /// existing shape checks to reject it naturally.
-    fn resolve(&self, value: &'a Value) -> &'a Value {
-        value
-            .as_str()
-            .and_then(|link| link.split_whitespace().next())
-            .and_then(|address| address.parse::<u64>().ok())
-            .and_then(|address| self.by_address.get(&address).copied())
-            .unwrap_or(value)
-    }
+            Some(UnsupportedParameter {
+                name: name.to_string(),
+                ty: member["type"].as_str().unwrap_or_default().to_string(),
+                value: member["value"].as_str().map(str::to_string),
+            })
+        })
+        .collect()
 }
 
-fn descend_into_generate_block<'a>(
+pub(crate) fn descend_into_generate_block<'a>(
     value: &'a Value,
     hier_prefix: String,
     symbol_table: &HashSet<String>,
 ) -> Option<(String, &'a Value)> {
-    if let Some(name) = value.get("name").and_then(|v| v.as_str()) {
-        if let Some(index) = value.get("constructIndex").and_then(|v| v.as_i64()) {
-            if let Some(uninstantiated) = value.get("isUninstantiated").and_then(|v| v.as_bool()) {
-                if uninstantiated {
-                    return None;
-                }
-                let genblk_name = if name.is_empty() {
-                    get_default_genblk_name(index as usize, symbol_table)
-                } else {
-                    name.to_string()
-                };
-                let hier_prefix = format!("{hier_prefix}.{genblk_name}");
-                return Some((hier_prefix, value));
-            }
-        }
+    if value.get("isUninstantiated").and_then(|v| v.as_bool())? {
+        return None;
     }
-
-    None
+    let (genblk_name, _) = generate_block_name(value, symbol_table)?;
+    Some((format!("{hier_prefix}.{genblk_name}"), value))
 }
 
-fn descend_into_generate_block_array<'a>(
+pub(crate) fn descend_into_generate_block_array<'a>(
     value: &'a Value,
     hier_prefix: String,
     symbol_table: &HashSet<String>,
 ) -> Option<Vec<(String, &'a Value)>> {
-    if let Some(name) = value.get("name").and_then(|v| v.as_str()) {
-        if let Some(index) = value.get("constructIndex").and_then(|v| v.as_i64()) {
-            if let Some(members) = value.get("members").and_then(|v| v.as_array()) {
-                let genblk_name = if name.is_empty() {
-                    get_default_genblk_name(index as usize, symbol_table)
-                } else {
-                    name.to_string()
-                };
-                let hier_prefix = format!("{hier_prefix}.{genblk_name}");
-
-                let mut elements = Vec::new();
-                for member in members {
-                    if let Some(kind) = member.get("kind") {
-                        if kind == "GenerateBlock" {
-                            if let Some(subidx) =
-                                member.get("constructIndex").and_then(|v| v.as_i64())
-                            {
-                                elements.push((format!("{hier_prefix}[{subidx}]"), member));
-                            }
-                        }
-                    }
+    let (genblk_name, _) = generate_block_name(value, symbol_table)?;
+    value.get("members").and_then(|v| v.as_array())?;
+    let hier_prefix = format!("{hier_prefix}.{genblk_name}");
+    Some(
+        generate_block_array_elements(value)
+            .filter_map(|element| {
+                let index = generate_array_index(element)?;
+                Some((format!("{hier_prefix}[{index}]"), element))
+            })
+            .collect(),
+    )
+}
+
+/// The name of a generate block or generate loop, and whether it was
+/// assigned by the language because the construct is unnamed.
+fn generate_block_name(value: &Value, symbol_table: &HashSet<String>) -> Option<(String, bool)> {
+    let name = value.get("name").and_then(|v| v.as_str())?;
+    let index = value.get("constructIndex").and_then(|v| v.as_i64())?;
+    if name.is_empty() {
+        Some((get_default_genblk_name(index as usize, symbol_table), true))
+    } else {
+        Some((name.to_string(), false))
+    }
+}
+
+fn generate_block_array_elements(value: &Value) -> impl Iterator<Item = &Value> {
+    value
+        .get("members")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|member| {
+            member
+                .get("kind")
+                .is_some_and(|kind| kind == "GenerateBlock")
+        })
+}
+
+/// The index of one iteration of a generate loop: the value of its genvar
+/// where Slang reports it, and its position in the loop otherwise.
+fn generate_array_index(element: &Value) -> Option<i64> {
+    element
+        .get("arrayIndex")
+        .and_then(|v| {
+            v.as_i64().or_else(|| {
+                parse_integer_literal(v.as_str()?).and_then(|(_, _, value)| value.to_i64())
+            })
+        })
+        .or_else(|| element.get("constructIndex").and_then(|v| v.as_i64()))
+}
+
+/// The genvar of a generate loop, which Slang declares as a local parameter
+/// at the start of every iteration.
+fn loop_variable(element: &Value) -> Option<String> {
+    let first = element.get("members")?.as_array()?.first()?;
+    if first.get("kind")? != "Parameter" {
+        return None;
+    }
+    Some(first.get("name")?.as_str()?.to_string())
+}
+
+/// The instance body and the instantiated generate blocks within it, with
+/// the prefix each one adds to hierarchical paths.
+pub(crate) fn instantiated_scopes(body: &Value) -> Vec<(&Value, String)> {
+    fn visit<'a>(scope: &'a Value, hier_prefix: String, scopes: &mut Vec<(&'a Value, String)>) {
+        let symbol_table = create_symbol_table(scope);
+        scopes.push((scope, hier_prefix.clone()));
+        for member in MemberIter::new(scope, &["GenerateBlock", "GenerateBlockArray"]) {
+            if member["kind"] == "GenerateBlock" {
+                if let Some((hier_prefix, block)) =
+                    descend_into_generate_block(member, hier_prefix.clone(), &symbol_table)
+                {
+                    visit(block, hier_prefix, scopes);
+                }
+            } else if let Some(elements) =
+                descend_into_generate_block_array(member, hier_prefix.clone(), &symbol_table)
+            {
+                for (hier_prefix, element) in elements {
+                    visit(element, hier_prefix, scopes);
                 }
-                return Some(elements);
             }
         }
     }
 
-    None
+    let mut scopes = Vec::new();
+    visit(body, String::new(), &mut scopes);
+    scopes
 }
 
-fn create_symbol_table(value: &Value) -> HashSet<String> {
+pub(crate) fn create_symbol_table(value: &Value) -> HashSet<String> {
     let mut table = HashSet::new();
     if let Some(members) = value.get("members").and_then(|v| v.as_array()) {
         for member in members {
@@ -319,4 +998,441 @@ mod tests {
         assert_eq!(children[1].borrow().inst_name, "second");
         assert_eq!(children[1].borrow().def_name, "child");
     }
+
+    #[test]
+    fn builds_navigable_hierarchy_tree() {
+        let ast = json!({
+            "design": {
+                "members": [{
+                    "name": "top",
+                    "kind": "Instance",
+                    "body": {
+                        "name": "top",
+                        "kind": "InstanceBody",
+                        "members": [
+                            {
+                                "name": "u_core",
+                                "kind": "Instance",
+                                "body": {
+                                    "name": "core",
+                                    "kind": "InstanceBody",
+                                    "members": [{
+                                        "name": "",
+                                        "kind": "GenerateBlock",
+                                        "constructIndex": 1,
+                                        "isUninstantiated": false,
+                                        "members": [{
+                                            "name": "u_alu",
+                                            "kind": "Instance",
+                                            "body": { "name": "alu", "kind": "InstanceBody" }
+                                        }]
+                                    }]
+                                }
+                            },
+                            {
+                                "name": "u_fifo_a",
+                                "kind": "Instance",
+                                "body": { "name": "fifo", "kind": "InstanceBody" }
+                            }
+                        ]
+                    }
+                }]
+            }
+        });
+
+        let hierarchy = extract_hierarchy_tree_from_value(&ast);
+        assert_eq!(hierarchy.len(), 4);
+
+        let top = hierarchy.roots()[0];
+        let alu = hierarchy.find("top.u_core.genblk1.u_alu").unwrap();
+        assert_eq!(hierarchy[alu].def_name, "alu");
+        assert_eq!(hierarchy[alu].hier_prefix, ".genblk1");
+        let core = hierarchy.parent(alu).unwrap();
+        assert_eq!(hierarchy.path(core), "top.u_core");
+        assert_eq!(hierarchy.parent(core), Some(top));
+        assert_eq!(hierarchy.parent(top), None);
+        assert_eq!(hierarchy.children(core), &[alu]);
+        assert_eq!(hierarchy.find("top.u_alu"), None);
+
+        let paths = |ids: Vec<InstanceId>| -> Vec<String> {
+            ids.into_iter().map(|id| hierarchy.path(id)).collect()
+        };
+        assert_eq!(
+            paths(hierarchy.depth_first().collect()),
+            vec![
+                "top",
+                "top.u_core",
+                "top.u_core.genblk1.u_alu",
+                "top.u_fifo_a"
+            ]
+        );
+        assert_eq!(
+            paths(hierarchy.breadth_first().collect()),
+            vec![
+                "top",
+                "top.u_core",
+                "top.u_fifo_a",
+                "top.u_core.genblk1.u_alu"
+            ]
+        );
+        assert_eq!(
+            paths(hierarchy.glob("top.u_*")),
+            vec!["top.u_core", "top.u_fifo_a"]
+        );
+        assert_eq!(
+            paths(hierarchy.glob("top.**.u_?lu")),
+            vec!["top.u_core.genblk1.u_alu"]
+        );
+        assert_eq!(hierarchy.glob("**").len(), 4);
+
+        let instances = hierarchy.to_instances();
+        let core = instances["top"].contents[0].borrow();
+        assert_eq!(core.inst_name, "u_core");
+        assert_eq!(core.contents[0].borrow().inst_name, "u_alu");
+
+        fn assert_send<T: Send>(_: &T) {}
+        assert_send(&hierarchy);
+    }
+
+    #[test]
+    fn groups_instances_by_parameters() {
+        let mem = |addr: u64, depth: &str, overridden: bool, ty: &str, scale: &str| {
+            json!({
+                "name": "mem",
+                "kind": "InstanceBody",
+                "addr": addr,
+                "members": [
+                    {
+                        "kind": "Parameter",
+                        "name": "DEPTH",
+                        "type": "int",
+                        "value": depth,
+                        "isOverridden": overridden
+                    },
+                    { "kind": "TypeParameter", "name": "T", "type": ty },
+                    { "kind": "Parameter", "name": "SCALE", "type": "real$[0:1]", "value": scale }
+                ]
+            })
+        };
+        let ast = json!({
+            "nodes": [{ "kind": "TypeAlias", "addr": 100, "target": "logic[7:0]" }],
+            "design": {
+                "members": [{
+                    "name": "top",
+                    "kind": "Instance",
+                    "body": {
+                        "name": "top",
+                        "kind": "InstanceBody",
+                        "members": [
+                            {
+                                "name": "u_a",
+                                "kind": "Instance",
+                                "body": mem(1, "16", false, "logic[7:0]", "'{1,1}")
+                            },
+                            {
+                                "name": "u_b",
+                                "kind": "Instance",
+                                "body": mem(2, "1024", true, "logic[7:0]", "'{1,1}")
+                            },
+                            {
+                                "name": "u_c",
+                                "kind": "Instance",
+                                "body": mem(3, "16", true, "100 byte_t", "'{1,1}")
+                            },
+                            {
+                                "name": "u_d",
+                                "kind": "Instance",
+                                "body": mem(4, "16", false, "logic[7:0]", "'{0.5,0.5}")
+                            }
+                        ]
+                    }
+                }]
+            }
+        });
+
+        let hierarchy = extract_hierarchy_tree_from_value(&ast);
+        let u_b = hierarchy.find("top.u_b").unwrap();
+        assert_eq!(hierarchy[u_b].parameters[0].value.as_deref(), Some("1024"));
+        assert_eq!(
+            hierarchy[u_b].unsupported_parameters,
+            vec![UnsupportedParameter {
+                name: "SCALE".to_string(),
+                ty: "real$[0:1]".to_string(),
+                value: Some("'{1,1}".to_string()),
+            }]
+        );
+
+        let u_c = hierarchy.find("top.u_c").unwrap();
+        assert_eq!(
+            hierarchy[u_c].parameters[1].ty.as_ref().unwrap().aliases(),
+            &["byte_t"]
+        );
+
+        let groups = hierarchy.group_by_parameters();
+        let paths: Vec<(&str, Vec<String>)> = groups
+            .iter()
+            .map(|group| {
+                (
+                    group.def_name.as_str(),
+                    group
+                        .instances
+                        .iter()
+                        .map(|&id| hierarchy.path(id))
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("top", vec!["top".to_string()]),
+                ("mem", vec!["top.u_a".to_string(), "top.u_c".to_string()]),
+                ("mem", vec!["top.u_b".to_string()]),
+                ("mem", vec!["top.u_d".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn distinguishes_instance_kinds() {
+        let ast = json!({
+            "design": {
+                "members": [{
+                    "name": "top",
+                    "kind": "Instance",
+                    "body": {
+                        "name": "top",
+                        "kind": "InstanceBody",
+                        "definition": "1 top",
+                        "members": [
+                            {
+                                "name": "u_bus",
+                                "kind": "Instance",
+                                "body": {
+                                    "name": "bus_if",
+                                    "kind": "InstanceBody",
+                                    "members": [{
+                                        "name": "u_mon",
+                                        "kind": "Instance",
+                                        "body": { "name": "bus_mon", "kind": "InstanceBody" }
+                                    }]
+                                }
+                            },
+                            {
+                                "name": "u_test",
+                                "kind": "Instance",
+                                "body": { "name": "test", "kind": "InstanceBody", "definition": "3 test" }
+                            },
+                            {
+                                "name": "u_chk",
+                                "kind": "CheckerInstance",
+                                "body": { "name": "stable_chk", "kind": "CheckerInstanceBody" }
+                            },
+                            { "name": "g1", "kind": "PrimitiveInstance", "primitiveType": "4 and" },
+                            {
+                                "name": "u_missing",
+                                "kind": "UninstantiatedDef",
+                                "definitionName": "missing"
+                            }
+                        ]
+                    }
+                }]
+            },
+            "definitions": [
+                { "name": "top", "kind": "Definition", "addr": 1, "definitionKind": "Module" },
+                { "name": "bus_if", "kind": "Definition", "addr": 2, "definitionKind": "Interface" },
+                { "name": "test", "kind": "Definition", "addr": 3, "definitionKind": "Program" }
+            ]
+        });
+
+        let hierarchy = extract_hierarchy_tree_from_value(&ast);
+        let kinds: Vec<(String, InstanceKind)> = hierarchy
+            .depth_first()
+            .map(|id| (hierarchy.path(id), hierarchy[id].kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("top".to_string(), InstanceKind::Module),
+                ("top.u_bus".to_string(), InstanceKind::Interface),
+                ("top.u_bus.u_mon".to_string(), InstanceKind::Module),
+                ("top.u_test".to_string(), InstanceKind::Program),
+                ("top.u_chk".to_string(), InstanceKind::Checker),
+                ("top.g1".to_string(), InstanceKind::Primitive),
+                ("top.u_missing".to_string(), InstanceKind::Uninstantiated),
+            ]
+        );
+        assert_eq!(hierarchy[hierarchy.find("top.g1").unwrap()].def_name, "and");
+    }
+
+    #[test]
+    fn follows_instance_arrays() {
+        let leaf = json!({
+            "name": "",
+            "kind": "Instance",
+            "body": { "name": "leaf", "kind": "InstanceBody" }
+        });
+        let ast = json!({
+            "design": {
+                "members": [{
+                    "name": "top",
+                    "kind": "Instance",
+                    "body": {
+                        "name": "top",
+                        "kind": "InstanceBody",
+                        "members": [
+                            {
+                                "name": "u",
+                                "kind": "InstanceArray",
+                                "range": "[3:2]",
+                                "members": [leaf, leaf]
+                            },
+                            {
+                                "name": "u_grid",
+                                "kind": "InstanceArray",
+                                "range": "[0:1]",
+                                "members": [
+                                    { "name": "", "kind": "InstanceArray", "range": "[1:1]", "members": [leaf] },
+                                    { "name": "", "kind": "InstanceArray", "range": "[1:1]", "members": [leaf] }
+                                ]
+                            }
+                        ]
+                    }
+                }]
+            }
+        });
+
+        let hierarchy = extract_hierarchy_tree_from_value(&ast);
+        let paths: Vec<String> = hierarchy
+            .depth_first()
+            .map(|id| hierarchy.path(id))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "top",
+                "top.u[2]",
+                "top.u[3]",
+                "top.u_grid[0][1]",
+                "top.u_grid[1][1]"
+            ]
+        );
+        let element = hierarchy.find("top.u[3]").unwrap();
+        assert_eq!(hierarchy[element].def_name, "leaf");
+        assert_eq!(hierarchy.parent(element), hierarchy.find("top"));
+    }
+
+    #[test]
+    fn records_generate_blocks() {
+        let leaf = json!({ "name": "leaf", "kind": "InstanceBody", "addr": 2 });
+        let ast = json!({
+            "design": {
+                "members": [{
+                    "name": "top",
+                    "kind": "Instance",
+                    "body": {
+                        "name": "top",
+                        "kind": "InstanceBody",
+                        "members": [
+                            {
+                                "name": "",
+                                "kind": "GenerateBlock",
+                                "constructIndex": 1,
+                                "isUninstantiated": true,
+                                "members": []
+                            },
+                            {
+                                "name": "",
+                                "kind": "GenerateBlock",
+                                "constructIndex": 1,
+                                "isUninstantiated": false,
+                                "members": [{ "name": "u_b", "kind": "Instance", "body": leaf }]
+                            },
+                            {
+                                "name": "g",
+                                "kind": "GenerateBlockArray",
+                                "constructIndex": 2,
+                                "members": [{
+                                    "name": "",
+                                    "kind": "GenerateBlock",
+                                    "constructIndex": 0,
+                                    "arrayIndex": "2",
+                                    "isUninstantiated": false,
+                                    "members": [
+                                        {
+                                            "name": "i",
+                                            "kind": "Parameter",
+                                            "type": "int",
+                                            "value": "2",
+                                            "isLocal": true
+                                        },
+                                        {
+                                            "name": "inner",
+                                            "kind": "GenerateBlock",
+                                            "constructIndex": 1,
+                                            "isUninstantiated": false,
+                                            "members": [
+                                                { "name": "u_c", "kind": "Instance", "body": "2 leaf" }
+                                            ]
+                                        }
+                                    ]
+                                }]
+                            }
+                        ]
+                    }
+                }]
+            }
+        });
+
+        let hierarchy = extract_hierarchy_tree_from_value(&ast);
+        let top = hierarchy.roots()[0];
+        let blocks: Vec<GenerateBlockId> = hierarchy.generate_blocks().collect();
+        assert_eq!(blocks.len(), 4);
+
+        let pruned = &hierarchy[blocks[0]];
+        assert_eq!(pruned.name, "genblk1");
+        assert!(pruned.auto_named);
+        assert!(pruned.uninstantiated);
+        assert_eq!(
+            pruned.construct,
+            GenerateConstruct::Conditional { branch: 0 }
+        );
+
+        let taken = &hierarchy[blocks[1]];
+        assert!(!taken.uninstantiated);
+        assert_eq!(
+            taken.construct,
+            GenerateConstruct::Conditional { branch: 1 }
+        );
+        let b = hierarchy.find("top.genblk1.u_b").unwrap();
+        assert_eq!(hierarchy.generate_block(b), Some(blocks[1]));
+        assert_eq!(taken.members(), &[ScopeMember::Instance(b)]);
+
+        let element = &hierarchy[blocks[2]];
+        assert_eq!(element.name, "g");
+        assert!(!element.auto_named);
+        assert_eq!(
+            element.construct,
+            GenerateConstruct::Loop {
+                variable: Some("i".to_string()),
+                value: 2
+            }
+        );
+        assert_eq!(hierarchy.generate_block_path(blocks[3]), "top.g[2].inner");
+        assert_eq!(hierarchy[blocks[3]].parent(), Some(blocks[2]));
+        assert_eq!(hierarchy[blocks[3]].instance(), top);
+
+        let c = hierarchy.find("top.g[2].inner.u_c").unwrap();
+        assert_eq!(hierarchy.parent(c), Some(top));
+        assert_eq!(hierarchy.generate_block(c), Some(blocks[3]));
+        assert_eq!(
+            hierarchy.members(top),
+            &[
+                ScopeMember::GenerateBlock(blocks[0]),
+                ScopeMember::GenerateBlock(blocks[1]),
+                ScopeMember::GenerateBlock(blocks[2]),
+            ]
+        );
+        assert_eq!(hierarchy.children(top), &[b, c]);
+    }
 }
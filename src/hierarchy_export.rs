@@ -0,0 +1,499 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Renders a [`Hierarchy`] as a Graphviz graph, a JSON document or an
+//! indented text tree.
+//!
+//! Generate blocks appear as scopes of their own between an instance and the
+//! instances inside them, and definitions that Slang did not elaborate are
+//! marked as uninstantiated. Generate branches that were not taken are left
+//! out unless `include_untaken` is set, in which case they are shown empty and
+//! marked as such.
+
+use crate::{GenerateConstruct, Hierarchy, InstanceId, InstanceKind, ScopeMember};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Settings for [`generate_hierarchy_dot`].
+#[derive(Debug, Default)]
+pub struct DotOptions {
+    /// Draws one node per definition rather than one per instance, with each
+    /// edge labelled by the number of instances it stands for.
+    pub collapse_definitions: bool,
+    /// Omits instances more than this many levels below the top-level ones.
+    pub max_depth: Option<usize>,
+    /// Draws the generate branches that were not taken, as dashed folders.
+    pub include_untaken: bool,
+}
+
+/// The members of an instance body or generate block to export, leaving out
+/// the branches that were not taken unless `include_untaken` is set.
+fn members(
+    hierarchy: &Hierarchy,
+    members: &[ScopeMember],
+    include_untaken: bool,
+) -> Vec<ScopeMember> {
+    members
+        .iter()
+        .copied()
+        .filter(|member| match member {
+            ScopeMember::GenerateBlock(block) => {
+                include_untaken || !hierarchy[*block].uninstantiated
+            }
+            ScopeMember::Instance(_) => true,
+        })
+        .collect()
+}
+
+/// Prints the hierarchy as an indented tree, one entry per line, with the kind
+/// of every instance that is not a module:
+///
+/// ```text
+/// top : top
+///   u_core : core
+///     genblk1 (generate)
+///       u_alu : alu
+///     genblk2 (generate, not taken)
+///   u_missing : missing (uninstantiated)
+/// ```
+///
+/// Generate branches that were not taken are listed only if
+/// `include_untaken` is set.
+pub fn generate_hierarchy_text(hierarchy: &Hierarchy, include_untaken: bool) -> String {
+    fn instance(
+        out: &mut String,
+        hierarchy: &Hierarchy,
+        id: InstanceId,
+        depth: usize,
+        include_untaken: bool,
+    ) {
+        let node = &hierarchy[id];
+        let marker = match node.kind {
+            InstanceKind::Module => String::new(),
+            kind => format!(" ({})", kind_name(kind)),
+        };
+        writeln!(
+            out,
+            "{:indent$}{} : {}{marker}",
+            "",
+            node.inst_name,
+            node.def_name,
+            indent = 2 * depth
+        )
+        .unwrap();
+        let members = hierarchy.members(id);
+        entries(out, hierarchy, members, depth + 1, include_untaken);
+    }
+
+    fn entries(
+        out: &mut String,
+        hierarchy: &Hierarchy,
+        scope: &[ScopeMember],
+        depth: usize,
+        include_untaken: bool,
+    ) {
+        for member in members(hierarchy, scope, include_untaken) {
+            match member {
+                ScopeMember::Instance(id) => instance(out, hierarchy, id, depth, include_untaken),
+                ScopeMember::GenerateBlock(block) => {
+                    let block = &hierarchy[block];
+                    let marker = if block.uninstantiated {
+                        ", not taken"
+                    } else {
+                        ""
+                    };
+                    writeln!(
+                        out,
+                        "{:indent$}{} (generate{marker})",
+                        "",
+                        block.path_name(),
+                        indent = 2 * depth
+                    )
+                    .unwrap();
+                    entries(out, hierarchy, block.members(), depth + 1, include_untaken);
+                }
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for &root in hierarchy.roots() {
+        instance(&mut out, hierarchy, root, 0, include_untaken);
+    }
+    out
+}
+
+/// Converts the hierarchy into JSON with the schema below. Every entry carries
+/// its full hierarchical path, and entries are listed in design order. The
+/// `kind` of an instance is `module`, `interface`, `program`, `checker`,
+/// `primitive` or `uninstantiated`. A generate block is either a branch of an
+/// `if` or `case` generate or one iteration of a generate loop, and its `name`
+/// includes the iteration's index. Branches that were not taken are listed,
+/// with `uninstantiated` set and no children, only if `include_untaken` is
+/// set.
+///
+/// ```text
+/// { "roots": [entry, ...] }
+/// entry = { "type": "instance", "name", "path", "definition", "kind",
+///           "uninstantiated": bool, "children": [entry, ...] }
+///       | { "type": "generate", "name", "path", "auto_named": bool,
+///           "uninstantiated": bool, "construct": "conditional", "branch": n,
+///           "children": [entry, ...] }
+///       | { "type": "generate", "name", "path", "auto_named": bool,
+///           "uninstantiated": false, "construct": "loop",
+///           "loop_variable": name | null, "loop_value": n, "children": [entry, ...] }
+/// ```
+pub fn generate_hierarchy_json(hierarchy: &Hierarchy, include_untaken: bool) -> Value {
+    fn instance(hierarchy: &Hierarchy, id: InstanceId, include_untaken: bool) -> Value {
+        let node = &hierarchy[id];
+        json!({
+            "type": "instance",
+            "name": node.inst_name,
+            "path": hierarchy.path(id),
+            "definition": node.def_name,
+            "kind": kind_name(node.kind),
+            "uninstantiated": node.kind == InstanceKind::Uninstantiated,
+            "children": entries(hierarchy, hierarchy.members(id), include_untaken),
+        })
+    }
+
+    fn entries(hierarchy: &Hierarchy, scope: &[ScopeMember], include_untaken: bool) -> Vec<Value> {
+        members(hierarchy, scope, include_untaken)
+            .into_iter()
+            .map(|member| match member {
+                ScopeMember::Instance(id) => instance(hierarchy, id, include_untaken),
+                ScopeMember::GenerateBlock(id) => {
+                    let block = &hierarchy[id];
+                    let mut entry = json!({
+                        "type": "generate",
+                        "name": block.path_name(),
+                        "path": hierarchy.generate_block_path(id),
+                        "auto_named": block.auto_named,
+                        "uninstantiated": block.uninstantiated,
+                    });
+                    match &block.construct {
+                        GenerateConstruct::Conditional { branch } => {
+                            entry["construct"] = json!("conditional");
+                            entry["branch"] = json!(branch);
+                        }
+                        GenerateConstruct::Loop { variable, value } => {
+                            entry["construct"] = json!("loop");
+                            entry["loop_variable"] = json!(variable);
+                            entry["loop_value"] = json!(value);
+                        }
+                    }
+                    entry["children"] = json!(entries(hierarchy, block.members(), include_untaken));
+                    entry
+                }
+            })
+            .collect()
+    }
+
+    let roots: Vec<Value> = hierarchy
+        .roots()
+        .iter()
+        .map(|&root| instance(hierarchy, root, include_untaken))
+        .collect();
+    json!({ "roots": roots })
+}
+
+/// Draws the hierarchy as a Graphviz `digraph`. Instances are boxes,
+/// uninstantiated definitions dashed boxes and generate scopes folders, which
+/// are dashed for branches that were not taken.
+pub fn generate_hierarchy_dot(hierarchy: &Hierarchy, options: &DotOptions) -> String {
+    let mut out = String::from("digraph hierarchy {\n  node [shape=box];\n");
+    if options.collapse_definitions {
+        collapsed_dot(&mut out, hierarchy, options.max_depth);
+    } else {
+        let mut dot = TreeDot {
+            out: &mut out,
+            hierarchy,
+            max_depth: options.max_depth,
+            include_untaken: options.include_untaken,
+        };
+        for &root in hierarchy.roots() {
+            dot.instance(root, 0);
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+struct TreeDot<'a> {
+    out: &'a mut String,
+    hierarchy: &'a Hierarchy,
+    max_depth: Option<usize>,
+    include_untaken: bool,
+}
+
+impl TreeDot<'_> {
+    fn instance(&mut self, id: InstanceId, depth: usize) -> String {
+        let node = &self.hierarchy[id];
+        let name = format!("i{}", id.index());
+        let style = if node.kind == InstanceKind::Uninstantiated {
+            ", style=dashed"
+        } else {
+            ""
+        };
+        writeln!(
+            self.out,
+            "  {name} [label=\"{}\\n({})\"{style}];",
+            escape(&node.inst_name),
+            escape(&node.def_name)
+        )
+        .unwrap();
+        if self.max_depth.is_none_or(|max| depth < max) {
+            self.entries(&name, self.hierarchy.members(id), depth + 1);
+        }
+        name
+    }
+
+    fn entries(&mut self, parent: &str, scope: &[ScopeMember], depth: usize) {
+        let hierarchy = self.hierarchy;
+        for member in members(hierarchy, scope, self.include_untaken) {
+            let child = match member {
+                ScopeMember::Instance(id) => self.instance(id, depth),
+                ScopeMember::GenerateBlock(id) => {
+                    let block = &hierarchy[id];
+                    let scope = format!("s{}", id.index());
+                    let style = if block.uninstantiated {
+                        ", style=dashed"
+                    } else {
+                        ""
+                    };
+                    writeln!(
+                        self.out,
+                        "  {scope} [label=\"{}\", shape=folder{style}];",
+                        escape(&block.path_name())
+                    )
+                    .unwrap();
+                    self.entries(&scope, block.members(), depth);
+                    scope
+                }
+            };
+            writeln!(self.out, "  {parent} -> {child};").unwrap();
+        }
+    }
+}
+
+/// Draws one node per definition and one edge per parent and child
+/// definition, labelled with the number of such instances.
+fn collapsed_dot(out: &mut String, hierarchy: &Hierarchy, max_depth: Option<usize>) {
+    fn definition<'a>(
+        hierarchy: &'a Hierarchy,
+        id: InstanceId,
+        definitions: &mut Vec<(&'a str, bool)>,
+    ) -> usize {
+        let node = &hierarchy[id];
+        match definitions
+            .iter()
+            .position(|(name, _)| *name == node.def_name)
+        {
+            Some(index) => index,
+            None => {
+                definitions.push((&node.def_name, node.kind == InstanceKind::Uninstantiated));
+                definitions.len() - 1
+            }
+        }
+    }
+
+    let mut definitions: Vec<(&str, bool)> = Vec::new();
+    let mut edges: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+    let mut stack: Vec<(InstanceId, usize)> =
+        hierarchy.roots().iter().rev().map(|&id| (id, 0)).collect();
+    while let Some((id, depth)) = stack.pop() {
+        let parent = definition(hierarchy, id, &mut definitions);
+        if max_depth.is_some_and(|max| depth >= max) {
+            continue;
+        }
+        for &child in hierarchy.children(id).iter().rev() {
+            stack.push((child, depth + 1));
+        }
+        for &child in hierarchy.children(id) {
+            let child = definition(hierarchy, child, &mut definitions);
+            *edges.entry((parent, child)).or_default() += 1;
+        }
+    }
+
+    for (index, (name, uninstantiated)) in definitions.iter().enumerate() {
+        let style = if *uninstantiated {
+            ", style=dashed"
+        } else {
+            ""
+        };
+        writeln!(out, "  d{index} [label=\"{}\"{style}];", escape(name)).unwrap();
+    }
+    for ((parent, child), count) in edges {
+        writeln!(out, "  d{parent} -> d{child} [label=\"{count}\"];").unwrap();
+    }
+}
+
+fn kind_name(kind: InstanceKind) -> &'static str {
+    match kind {
+        InstanceKind::Module => "module",
+        InstanceKind::Interface => "interface",
+        InstanceKind::Program => "program",
+        InstanceKind::Checker => "checker",
+        InstanceKind::Primitive => "primitive",
+        InstanceKind::Uninstantiated => "uninstantiated",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract_hierarchy_tree_from_value;
+    use serde_json::json;
+
+    fn hierarchy() -> Hierarchy {
+        let leaf = json!({ "name": "leaf", "kind": "InstanceBody", "addr": 2 });
+        extract_hierarchy_tree_from_value(&json!({
+            "design": {
+                "members": [{
+                    "name": "top",
+                    "kind": "Instance",
+                    "body": {
+                        "name": "top",
+                        "kind": "InstanceBody",
+                        "members": [
+                            { "name": "u_a", "kind": "Instance", "body": leaf },
+                            {
+                                "name": "g",
+                                "kind": "GenerateBlockArray",
+                                "constructIndex": 1,
+                                "members": [
+                                    {
+                                        "name": "",
+                                        "kind": "GenerateBlock",
+                                        "constructIndex": 0,
+                                        "isUninstantiated": false,
+                                        "members": [
+                                            { "name": "u_b", "kind": "Instance", "body": "2 leaf" }
+                                        ]
+                                    }
+                                ]
+                            },
+                            {
+                                "name": "u_missing",
+                                "kind": "UninstantiatedDef",
+                                "definitionName": "missing"
+                            },
+                            {
+                                "name": "g_off",
+                                "kind": "GenerateBlock",
+                                "constructIndex": 2,
+                                "isUninstantiated": true
+                            }
+                        ]
+                    }
+                }]
+            }
+        }))
+    }
+
+    #[test]
+    fn prints_text_tree() {
+        assert_eq!(
+            generate_hierarchy_text(&hierarchy(), false),
+            "top : top
+  u_a : leaf
+  g[0] (generate)
+    u_b : leaf
+  u_missing : missing (uninstantiated)
+"
+        );
+        assert!(
+            generate_hierarchy_text(&hierarchy(), true).ends_with(
+                "  u_missing : missing (uninstantiated)\n  g_off (generate, not taken)\n"
+            )
+        );
+    }
+
+    #[test]
+    fn converts_to_json() {
+        let json = generate_hierarchy_json(&hierarchy(), false);
+        let top = &json["roots"][0];
+        assert_eq!(top["path"], "top");
+        assert_eq!(top["children"][1]["type"], "generate");
+        assert_eq!(top["children"][1]["path"], "top.g[0]");
+        assert_eq!(top["children"][1]["auto_named"], false);
+        assert_eq!(top["children"][1]["construct"], "loop");
+        assert_eq!(top["children"][1]["loop_value"], 0);
+        assert_eq!(top["children"][1]["children"][0]["path"], "top.g[0].u_b");
+        assert_eq!(top["children"][1]["children"][0]["definition"], "leaf");
+        assert_eq!(top["children"][0]["kind"], "module");
+        assert_eq!(top["children"][2]["kind"], "uninstantiated");
+        assert_eq!(top["children"][2]["uninstantiated"], true);
+        assert_eq!(top["children"][1]["uninstantiated"], false);
+        assert_eq!(top["children"].as_array().unwrap().len(), 3);
+
+        let json = generate_hierarchy_json(&hierarchy(), true);
+        let untaken = &json["roots"][0]["children"][3];
+        assert_eq!(untaken["path"], "top.g_off");
+        assert_eq!(untaken["uninstantiated"], true);
+        assert_eq!(untaken["branch"], 0);
+        assert_eq!(untaken["children"], json!([]));
+    }
+
+    #[test]
+    fn draws_dot_graphs() {
+        let hierarchy = hierarchy();
+        assert_eq!(
+            generate_hierarchy_dot(&hierarchy, &DotOptions::default()),
+            "digraph hierarchy {
+  node [shape=box];
+  i0 [label=\"top\\n(top)\"];
+  i1 [label=\"u_a\\n(leaf)\"];
+  i0 -> i1;
+  s0 [label=\"g[0]\", shape=folder];
+  i2 [label=\"u_b\\n(leaf)\"];
+  s0 -> i2;
+  i0 -> s0;
+  i3 [label=\"u_missing\\n(missing)\", style=dashed];
+  i0 -> i3;
+}
+"
+        );
+
+        let untaken = DotOptions {
+            include_untaken: true,
+            ..Default::default()
+        };
+        assert!(
+            generate_hierarchy_dot(&hierarchy, &untaken)
+                .ends_with("  s1 [label=\"g_off\", shape=folder, style=dashed];\n  i0 -> s1;\n}\n")
+        );
+
+        let collapsed = DotOptions {
+            collapse_definitions: true,
+            max_depth: None,
+            include_untaken: false,
+        };
+        assert_eq!(
+            generate_hierarchy_dot(&hierarchy, &collapsed),
+            "digraph hierarchy {
+  node [shape=box];
+  d0 [label=\"top\"];
+  d1 [label=\"leaf\"];
+  d2 [label=\"missing\", style=dashed];
+  d0 -> d1 [label=\"2\"];
+  d0 -> d2 [label=\"1\"];
+}
+"
+        );
+
+        let shallow = DotOptions {
+            collapse_definitions: false,
+            max_depth: Some(0),
+            include_untaken: false,
+        };
+        assert_eq!(
+            generate_hierarchy_dot(&hierarchy, &shallow),
+            "digraph hierarchy {\n  node [shape=box];\n  i0 [label=\"top\\n(top)\"];\n}\n"
+        );
+    }
+}
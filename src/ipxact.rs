@@ -0,0 +1,559 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Export of module interfaces as IP-XACT (IEEE 1685-2014) component
+//! descriptions.
+
+use crate::ConstValue;
+use crate::codegen::{range_length, strip_dimensions};
+use crate::extract::{ParameterDef, ParameterKind, Port, PortDir, Range, Type};
+use crate::flatten::flatten_port;
+use std::error::Error;
+use std::fmt::Write;
+
+/// Name shared by the generated view, component instantiation and file set.
+const VIEW_NAME: &str = "rtl";
+
+/// Settings for [`generate_ipxact`].
+#[derive(Debug)]
+pub struct IpXactOptions<'a> {
+    /// The VLNV (vendor, library, name, version) vendor of the component.
+    pub vendor: &'a str,
+    /// The VLNV library of the component.
+    pub library: &'a str,
+    /// The VLNV version of the component. The VLNV name is the module name.
+    pub version: &'a str,
+    /// Replaces each struct-typed port with one port per field, recursively.
+    pub flatten_structs: bool,
+    /// Replaces each aggregate port, including arrays and unions, with its
+    /// leaf signals as described in [`crate::flatten_port`]. Takes precedence
+    /// over `flatten_structs`.
+    pub flatten_ports: bool,
+    /// Joins port and field names when `flatten_structs` or `flatten_ports`
+    /// is set.
+    pub separator: &'a str,
+}
+
+impl<'a> Default for IpXactOptions<'a> {
+    fn default() -> Self {
+        IpXactOptions {
+            vendor: "unknown",
+            library: "unknown",
+            version: "1.0",
+            flatten_structs: false,
+            flatten_ports: false,
+            separator: "_",
+        }
+    }
+}
+
+/// Renders an IP-XACT `component` for `module`.
+///
+/// `ports` and `parameters` are the results of [`crate::extract_ports`] and
+/// [`crate::extract_parameter_defs`] for the module, and `cfg` is the
+/// configuration they were extracted with: its sources become the component's
+/// file set, and parameters without an elaborated value fall back to their
+/// `cfg.parameters` override. Parameters with neither are omitted, since
+/// IP-XACT requires every module parameter to have a value.
+///
+/// Each port lists one vector per packed dimension and one array per unpacked
+/// dimension. Ports of struct, union or enum type are described by a single
+/// vector spanning their full width, together with their type name.
+pub fn generate_ipxact(
+    module: &str,
+    ports: &[Port],
+    parameters: &[ParameterDef],
+    cfg: &crate::SlangConfig,
+    options: &IpXactOptions,
+) -> Result<String, Box<dyn Error>> {
+    let mut xml = String::new();
+    writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        xml,
+        r#"<ipxact:component xmlns:ipxact="http://www.accellera.org/XMLSchema/IPXACT/1685-2014" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://www.accellera.org/XMLSchema/IPXACT/1685-2014 http://www.accellera.org/XMLSchema/IPXACT/1685-2014/index.xsd">"#
+    )?;
+    element(&mut xml, 1, "vendor", options.vendor)?;
+    element(&mut xml, 1, "library", options.library)?;
+    element(&mut xml, 1, "name", module)?;
+    element(&mut xml, 1, "version", options.version)?;
+
+    open(&mut xml, 1, "model")?;
+    open(&mut xml, 2, "views")?;
+    open(&mut xml, 3, "view")?;
+    element(&mut xml, 4, "name", VIEW_NAME)?;
+    element(&mut xml, 4, "componentInstantiationRef", VIEW_NAME)?;
+    close(&mut xml, 3, "view")?;
+    close(&mut xml, 2, "views")?;
+
+    open(&mut xml, 2, "instantiations")?;
+    open(&mut xml, 3, "componentInstantiation")?;
+    element(&mut xml, 4, "name", VIEW_NAME)?;
+    element(&mut xml, 4, "language", "systemverilog")?;
+    element(&mut xml, 4, "moduleName", module)?;
+    // IP-XACT module parameters carry values, so type parameters are omitted.
+    let parameters: Vec<(&ParameterDef, &str)> = parameters
+        .iter()
+        .filter(|parameter| parameter.kind == ParameterKind::Value)
+        .filter_map(|parameter| {
+            let value = parameter.value.as_deref().or_else(|| {
+                cfg.parameters
+                    .iter()
+                    .find(|(name, _)| *name == parameter.name)
+                    .map(|(_, value)| *value)
+            })?;
+            Some((parameter, value))
+        })
+        .collect();
+    if !parameters.is_empty() {
+        open(&mut xml, 4, "moduleParameters")?;
+        for (parameter, value) in parameters {
+            writeln!(
+                xml,
+                r#"{}<ipxact:moduleParameter parameterId="{}" type="{}">"#,
+                indent(5),
+                escape(&parameter.name),
+                parameter_type(parameter)
+            )?;
+            element(&mut xml, 6, "name", &parameter.name)?;
+            element(&mut xml, 6, "value", value)?;
+            close(&mut xml, 5, "moduleParameter")?;
+        }
+        close(&mut xml, 4, "moduleParameters")?;
+    }
+    if !cfg.sources.is_empty() {
+        open(&mut xml, 4, "fileSetRef")?;
+        element(&mut xml, 5, "localName", VIEW_NAME)?;
+        close(&mut xml, 4, "fileSetRef")?;
+    }
+    close(&mut xml, 3, "componentInstantiation")?;
+    close(&mut xml, 2, "instantiations")?;
+
+    if !ports.is_empty() {
+        open(&mut xml, 2, "ports")?;
+        for port in ports {
+            if options.flatten_ports {
+                for leaf in flatten_port(port, options.separator)? {
+                    let ty = Type::Logic {
+                        signed: leaf.signed,
+                        packed_dimensions: if leaf.width > 1 {
+                            vec![Range {
+                                msb: leaf.width as i32 - 1,
+                                lsb: 0,
+                            }]
+                        } else {
+                            vec![]
+                        },
+                        unpacked_dimensions: vec![],
+                        aliases: vec![],
+                    };
+                    write_port(&mut xml, &leaf.name, &leaf.dir, &ty)?;
+                }
+            } else if options.flatten_structs {
+                for (name, ty) in flatten(&port.name, &port.ty, options.separator) {
+                    write_port(&mut xml, &name, &port.dir, ty)?;
+                }
+            } else {
+                write_port(&mut xml, &port.name, &port.dir, &port.ty)?;
+            }
+        }
+        close(&mut xml, 2, "ports")?;
+    }
+    close(&mut xml, 1, "model")?;
+
+    if !cfg.sources.is_empty() {
+        open(&mut xml, 1, "fileSets")?;
+        open(&mut xml, 2, "fileSet")?;
+        element(&mut xml, 3, "name", VIEW_NAME)?;
+        for source in cfg.sources {
+            open(&mut xml, 3, "file")?;
+            element(&mut xml, 4, "name", source)?;
+            element(&mut xml, 4, "fileType", file_type(source))?;
+            close(&mut xml, 3, "file")?;
+        }
+        close(&mut xml, 2, "fileSet")?;
+        close(&mut xml, 1, "fileSets")?;
+    }
+    writeln!(xml, "</ipxact:component>")?;
+    Ok(xml)
+}
+
+fn write_port(
+    xml: &mut String,
+    name: &str,
+    dir: &PortDir,
+    ty: &Type,
+) -> Result<(), Box<dyn Error>> {
+    open(xml, 3, "port")?;
+    element(xml, 4, "name", name)?;
+    open(xml, 4, "wire")?;
+    element(
+        xml,
+        5,
+        "direction",
+        match dir {
+            PortDir::Input => "in",
+            PortDir::Output => "out",
+            // IP-XACT has no `ref` direction; the closest is bidirectional.
+            PortDir::InOut | PortDir::Ref => "inout",
+        },
+    )?;
+    let vectors = match ty {
+        Type::Logic { .. } => ty.packed_dimensions().clone(),
+        _ => {
+            let unpacked = strip_dimensions(ty);
+            let element_width = unpacked.width()?;
+            let count: usize = ty.packed_dimensions().iter().map(range_length).product();
+            vec![Range {
+                msb: (element_width * count) as i32 - 1,
+                lsb: 0,
+            }]
+        }
+    };
+    if !vectors.is_empty() {
+        open(xml, 5, "vectors")?;
+        for range in vectors.iter() {
+            bounds(xml, 6, "vector", range)?;
+        }
+        close(xml, 5, "vectors")?;
+    }
+    if let Type::Struct { name, .. } | Type::Union { name, .. } | Type::Enum { name, .. } = ty {
+        open(xml, 5, "wireTypeDefs")?;
+        open(xml, 6, "wireTypeDef")?;
+        element(xml, 7, "typeName", name)?;
+        element(xml, 7, "viewRef", VIEW_NAME)?;
+        close(xml, 6, "wireTypeDef")?;
+        close(xml, 5, "wireTypeDefs")?;
+    }
+    close(xml, 4, "wire")?;
+    if !ty.unpacked_dimensions().is_empty() {
+        open(xml, 4, "arrays")?;
+        for range in ty.unpacked_dimensions() {
+            bounds(xml, 5, "array", range)?;
+        }
+        close(xml, 4, "arrays")?;
+    }
+    close(xml, 3, "port")
+}
+
+/// Expands a struct without dimensions into its fields, joining names with
+/// `separator`. Other types, including arrays of structs, are kept whole.
+fn flatten<'a>(name: &str, ty: &'a Type, separator: &str) -> Vec<(String, &'a Type)> {
+    match ty {
+        Type::Struct { fields, .. }
+            if ty.packed_dimensions().is_empty() && ty.unpacked_dimensions().is_empty() =>
+        {
+            fields
+                .iter()
+                .flat_map(|field| {
+                    flatten(
+                        &format!("{name}{separator}{}", field.name),
+                        &field.ty,
+                        separator,
+                    )
+                })
+                .collect()
+        }
+        _ => vec![(name.to_string(), ty)],
+    }
+}
+
+/// Chooses the IP-XACT `type` attribute of a module parameter. Unsigned and
+/// other non-`real`, non-`string` values are described as bit vectors.
+fn parameter_type(parameter: &ParameterDef) -> &'static str {
+    let Some(ty) = &parameter.ty else {
+        return match parameter.const_value() {
+            Ok(ConstValue::String(_)) => "string",
+            _ => "real",
+        };
+    };
+    match ty {
+        Type::Logic {
+            signed: true,
+            packed_dimensions,
+            ..
+        } if packed_dimensions
+            .iter()
+            .map(range_length)
+            .product::<usize>()
+            > 32 =>
+        {
+            "longint"
+        }
+        Type::Logic { signed: true, .. } => "int",
+        _ => "bit",
+    }
+}
+
+/// Maps a source file to its IP-XACT file type by extension.
+fn file_type(path: &str) -> &'static str {
+    match std::path::Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("sv") | Some("svh") => "systemVerilogSource",
+        Some("v") | Some("vh") => "verilogSource",
+        _ => "unknown",
+    }
+}
+
+fn bounds(xml: &mut String, depth: usize, tag: &str, range: &Range) -> Result<(), Box<dyn Error>> {
+    open(xml, depth, tag)?;
+    element(xml, depth + 1, "left", &range.msb.to_string())?;
+    element(xml, depth + 1, "right", &range.lsb.to_string())?;
+    close(xml, depth, tag)
+}
+
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth)
+}
+
+fn open(xml: &mut String, depth: usize, tag: &str) -> Result<(), Box<dyn Error>> {
+    writeln!(xml, "{}<ipxact:{tag}>", indent(depth))?;
+    Ok(())
+}
+
+fn close(xml: &mut String, depth: usize, tag: &str) -> Result<(), Box<dyn Error>> {
+    writeln!(xml, "{}</ipxact:{tag}>", indent(depth))?;
+    Ok(())
+}
+
+fn element(xml: &mut String, depth: usize, tag: &str, text: &str) -> Result<(), Box<dyn Error>> {
+    writeln!(
+        xml,
+        "{}<ipxact:{tag}>{}</ipxact:{tag}>",
+        indent(depth),
+        escape(text)
+    )?;
+    Ok(())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SlangConfig;
+    use crate::extract::{ParameterSource, parse_type_definition};
+
+    fn port(dir: PortDir, name: &str, ty: &str) -> Port {
+        Port {
+            dir,
+            name: name.to_string(),
+            ty: parse_type_definition(ty).unwrap(),
+            kind: None,
+            default: None,
+            expression: None,
+            location: None,
+            attributes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_component() {
+        let ports = vec![
+            port(PortDir::Input, "clk", "logic"),
+            port(PortDir::Input, "data", "logic[3:0][7:0]$[0:1]"),
+            port(
+                PortDir::Output,
+                "pkt",
+                "struct packed{logic[7:0] data;logic valid;}pkg::pkt_t",
+            ),
+        ];
+        let parameters = vec![
+            ParameterDef {
+                name: "WIDTH".to_string(),
+                ty: Some(parse_type_definition("logic signed[31:0]").unwrap()),
+                value: Some("8".to_string()),
+                kind: ParameterKind::Value,
+                is_local: false,
+                is_port: true,
+                source: ParameterSource::Default,
+                location: None,
+                attributes: vec![],
+            },
+            ParameterDef {
+                name: "DEPTH".to_string(),
+                ty: Some(parse_type_definition("logic signed[31:0]").unwrap()),
+                value: None,
+                kind: ParameterKind::Value,
+                is_local: false,
+                is_port: true,
+                source: ParameterSource::Default,
+                location: None,
+                attributes: vec![],
+            },
+            ParameterDef {
+                name: "T".to_string(),
+                ty: Some(parse_type_definition("logic[7:0]").unwrap()),
+                value: None,
+                kind: ParameterKind::Type,
+                is_local: false,
+                is_port: true,
+                source: ParameterSource::CommandLine,
+                location: None,
+                attributes: vec![],
+            },
+        ];
+        let cfg = SlangConfig {
+            sources: &["pkg.sv", "top.sv", "defs.vh"],
+            ..Default::default()
+        };
+        let options = IpXactOptions {
+            vendor: "acme",
+            library: "ip",
+            ..Default::default()
+        };
+
+        let xml = generate_ipxact("top", &ports, &parameters, &cfg, &options).unwrap();
+        assert!(xml.contains("  <ipxact:vendor>acme</ipxact:vendor>\n"));
+        assert!(xml.contains("  <ipxact:name>top</ipxact:name>\n"));
+        assert!(xml.contains("  <ipxact:version>1.0</ipxact:version>\n"));
+        assert!(xml.contains(
+            r#"          <ipxact:moduleParameter parameterId="WIDTH" type="int">
+            <ipxact:name>WIDTH</ipxact:name>
+            <ipxact:value>8</ipxact:value>
+          </ipxact:moduleParameter>
+"#
+        ));
+        assert!(!xml.contains("DEPTH"));
+        assert!(xml.contains(
+            "      <ipxact:port>
+        <ipxact:name>clk</ipxact:name>
+        <ipxact:wire>
+          <ipxact:direction>in</ipxact:direction>
+        </ipxact:wire>
+      </ipxact:port>
+"
+        ));
+        assert!(xml.contains(
+            "      <ipxact:port>
+        <ipxact:name>data</ipxact:name>
+        <ipxact:wire>
+          <ipxact:direction>in</ipxact:direction>
+          <ipxact:vectors>
+            <ipxact:vector>
+              <ipxact:left>3</ipxact:left>
+              <ipxact:right>0</ipxact:right>
+            </ipxact:vector>
+            <ipxact:vector>
+              <ipxact:left>7</ipxact:left>
+              <ipxact:right>0</ipxact:right>
+            </ipxact:vector>
+          </ipxact:vectors>
+        </ipxact:wire>
+        <ipxact:arrays>
+          <ipxact:array>
+            <ipxact:left>0</ipxact:left>
+            <ipxact:right>1</ipxact:right>
+          </ipxact:array>
+        </ipxact:arrays>
+      </ipxact:port>
+"
+        ));
+        assert!(xml.contains(
+            "      <ipxact:port>
+        <ipxact:name>pkt</ipxact:name>
+        <ipxact:wire>
+          <ipxact:direction>out</ipxact:direction>
+          <ipxact:vectors>
+            <ipxact:vector>
+              <ipxact:left>8</ipxact:left>
+              <ipxact:right>0</ipxact:right>
+            </ipxact:vector>
+          </ipxact:vectors>
+          <ipxact:wireTypeDefs>
+            <ipxact:wireTypeDef>
+              <ipxact:typeName>pkg::pkt_t</ipxact:typeName>
+              <ipxact:viewRef>rtl</ipxact:viewRef>
+            </ipxact:wireTypeDef>
+          </ipxact:wireTypeDefs>
+        </ipxact:wire>
+      </ipxact:port>
+"
+        ));
+        assert!(xml.contains(
+            "      <ipxact:file>
+        <ipxact:name>defs.vh</ipxact:name>
+        <ipxact:fileType>verilogSource</ipxact:fileType>
+      </ipxact:file>
+"
+        ));
+        assert!(!xml.contains("parameterId=\"T\""));
+        assert!(xml.ends_with("</ipxact:component>\n"));
+    }
+
+    #[test]
+    fn test_flatten_structs() {
+        let ports = vec![port(
+            PortDir::InOut,
+            "bus",
+            "struct packed{struct packed{logic[3:0] id;logic last;}hdr_t hdr;\
+             logic[1:0][7:0] data;}bus_t",
+        )];
+        let cfg = SlangConfig::default();
+        let options = IpXactOptions {
+            flatten_structs: true,
+            separator: "__",
+            ..Default::default()
+        };
+
+        let xml = generate_ipxact("top", &ports, &[], &cfg, &options).unwrap();
+        let names: Vec<&str> = xml
+            .lines()
+            .filter(|line| line.starts_with("        <ipxact:name>bus"))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "        <ipxact:name>bus__hdr__id</ipxact:name>",
+                "        <ipxact:name>bus__hdr__last</ipxact:name>",
+                "        <ipxact:name>bus__data</ipxact:name>",
+            ]
+        );
+        assert!(!xml.contains("typeName"));
+        assert!(!xml.contains("fileSet"));
+        assert!(!xml.contains("moduleParameters"));
+    }
+
+    #[test]
+    fn test_flatten_ports() {
+        let ports = vec![port(
+            PortDir::InOut,
+            "bus",
+            "struct packed{struct packed{logic[3:0] id;logic last;}hdr_t hdr;\
+             logic[1:0][7:0] data;}bus_t",
+        )];
+        let cfg = SlangConfig::default();
+        let options = IpXactOptions {
+            flatten_structs: true,
+            flatten_ports: true,
+            separator: "__",
+            ..Default::default()
+        };
+
+        let xml = generate_ipxact("top", &ports, &[], &cfg, &options).unwrap();
+        let names: Vec<&str> = xml
+            .lines()
+            .filter(|line| line.starts_with("        <ipxact:name>bus"))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "        <ipxact:name>bus__hdr__id</ipxact:name>",
+                "        <ipxact:name>bus__hdr__last</ipxact:name>",
+                "        <ipxact:name>bus__data__1</ipxact:name>",
+                "        <ipxact:name>bus__data__0</ipxact:name>",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a<b & \"c\""), "a&lt;b &amp; &quot;c&quot;");
+    }
+}
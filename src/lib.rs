@@ -5,18 +5,85 @@ use serde_json::Value;
 use std::fs::{self, write};
 use std::process::{Command, Stdio};
 
+mod attributes;
+pub use attributes::Attribute;
+
+mod connections;
+pub use connections::{ConnectionStyle, PortConnection};
+
+mod const_value;
+pub use const_value::{ConstValue, ConstValueError};
+
 mod extract;
 pub use extract::{
-    Field, ParameterDef, Port, PortDir, Range, Type, Variant, extract_modules,
-    extract_modules_from_value, extract_parameter_defs, extract_parameter_defs_from_value,
-    extract_ports, extract_ports_from_value, parse_type_definition,
+    Field, ParameterDef, ParameterKind, ParameterSource, Port, PortDir, PortKind, Range, Type,
+    TypeMismatch, TypeRelation, Variant, extract_module_attributes,
+    extract_module_attributes_from_value, extract_modules, extract_modules_from_value,
+    extract_parameter_defs, extract_parameter_defs_from_value, extract_ports,
+    extract_ports_from_value, parse_type_definition,
 };
 
+mod codegen;
+
+mod cpp;
+pub use cpp::generate_cpp_header;
+
+mod dslx;
+pub use dslx::{dslx_type, generate_dslx};
+
+mod expression;
+
+mod flatten;
+pub use flatten::{FlatPort, flatten_port, flatten_ports};
+
 mod hierarchy;
-pub use hierarchy::{Instance, extract_hierarchy, extract_hierarchy_from_value};
+pub use hierarchy::{
+    GenerateBlock, GenerateBlockId, GenerateConstruct, Hierarchy, HierarchyNode, Instance,
+    InstanceGroup, InstanceId, InstanceKind, ScopeMember, UnsupportedParameter, extract_hierarchy,
+    extract_hierarchy_from_value, extract_hierarchy_tree, extract_hierarchy_tree_from_value,
+};
+
+mod hierarchy_export;
+pub use hierarchy_export::{
+    DotOptions, generate_hierarchy_dot, generate_hierarchy_json, generate_hierarchy_text,
+};
+
+mod ipxact;
+pub use ipxact::{IpXactOptions, generate_ipxact};
+
+mod location;
+pub use location::SourceLocation;
 
 mod package;
-pub use package::{Package, Parameter, extract_packages, extract_packages_from_value};
+pub use package::{
+    Import, Package, Parameter, Subroutine, SubroutineArgument, SubroutineKind, extract_packages,
+    extract_packages_from_value,
+};
+
+mod package_graph;
+pub use package_graph::{
+    DependencyCycle, PackageGraph, compile_order, extract_package_graph,
+    extract_package_graph_from_value,
+};
+
+mod references;
+pub use references::{
+    HierarchicalReference, ReferenceDirection, extract_hierarchical_references,
+    extract_hierarchical_references_from_value,
+};
+
+mod signals;
+pub use signals::{
+    Signal, SignalDriver, SignalInventory, SignalKind, extract_signals, extract_signals_from_value,
+};
+
+mod specialization;
+pub use specialization::{
+    Specialization, extract_specializations, extract_specializations_from_value,
+};
+
+mod sweep;
+pub use sweep::{ParameterSweep, PortSweep, SweepRun, sweep_parameters};
 
 #[derive(Debug)]
 pub struct SlangConfig<'a> {
@@ -31,6 +98,9 @@ pub struct SlangConfig<'a> {
     pub ignore_unknown_modules: bool,
     pub ignore_protected: bool,
     pub capture_stdio: bool,
+    /// Asks Slang to record where each symbol is declared, which fills in the
+    /// `location` of extracted ports, parameters, instances and packages.
+    pub include_source_info: bool,
     pub timescale: Option<&'a str>,
     pub extra_arguments: &'a [&'a str],
 }
@@ -49,6 +119,7 @@ impl<'a> Default for SlangConfig<'a> {
             ignore_unknown_modules: true,
             ignore_protected: true,
             capture_stdio: true,
+            include_source_info: false,
             timescale: None,
             extra_arguments: &[],
         }
@@ -116,6 +187,10 @@ pub fn run_slang(cfg: &SlangConfig) -> Result<Value, Box<dyn std::error::Error>>
         push_options_to_ignore_protected(&mut args);
     }
 
+    if cfg.include_source_info {
+        args.push("--ast-json-source-info");
+    }
+
     for extra_arg in cfg.extra_arguments.iter() {
         args.push(extra_arg);
     }
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Source locations of extracted items, which Slang only reports when run
+//! with `SlangConfig::include_source_info`.
+
+use serde_json::Value;
+
+/// Where an extracted item is declared, as reported by Slang.
+///
+/// Locations are only available when Slang is run with
+/// `SlangConfig::include_source_info` set; otherwise they are `None`.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct SourceLocation {
+    pub file: String,
+    /// One-based line number.
+    pub line: usize,
+    /// One-based column number.
+    pub column: usize,
+}
+
+/// Reads the `source_file`, `source_line` and `source_column` properties
+/// that `--ast-json-source-info` adds to each serialized symbol.
+pub(crate) fn source_location(value: &Value) -> Option<SourceLocation> {
+    Some(SourceLocation {
+        file: value.get("source_file")?.as_str()?.to_string(),
+        line: value.get("source_line")?.as_u64()? as usize,
+        column: value.get("source_column")?.as_u64()? as usize,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_source_info() {
+        let symbol = json!({
+            "name": "clk",
+            "kind": "Port",
+            "source_file": "rtl/top.sv",
+            "source_line": 3,
+            "source_column": 11
+        });
+        assert_eq!(
+            source_location(&symbol),
+            Some(SourceLocation {
+                file: "rtl/top.sv".to_string(),
+                line: 3,
+                column: 11,
+            })
+        );
+        assert_eq!(source_location(&json!({ "name": "clk" })), None);
+    }
+}
@@ -1,5 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::extract::{TypeResolver, parse_resolved_type};
+use crate::location::{SourceLocation, source_location};
+use crate::{ConstValue, ConstValueError, PortDir, Type, Variant};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::error::Error;
@@ -7,10 +10,12 @@ use std::ops::Index;
 use std::str::FromStr;
 
 #[derive(Debug, PartialEq)]
-
 pub struct Parameter {
     pub name: String,
     pub value: String,
+    /// The declared type, if the grammar supports it.
+    pub ty: Option<Type>,
+    pub location: Option<SourceLocation>,
 }
 
 impl Parameter {
@@ -34,7 +39,7 @@ impl Parameter {
     /// use std::convert::TryFrom;
     ///
     /// # use slang_rs::Parameter;
-    /// let p = Parameter { name: "answer".into(), value: "42".into() };
+    /// let p = Parameter { name: "answer".into(), value: "42".into(), ty: None, location: None };
     ///
     /// // Primitive integer (type inferred)
     /// let n: i32 = p.parse().unwrap();
@@ -52,12 +57,44 @@ impl Parameter {
     {
         self.value.parse()
     }
+
+    /// Parses the parameter's `value` as Slang prints it, which handles sized
+    /// literals such as `8'hff`, X and Z bits, reals, strings and aggregates
+    /// that [`Parameter::parse`] cannot. When the type is known, the fields
+    /// of unpacked struct values are named after it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use slang_rs::{ConstValue, Parameter};
+    /// let p = Parameter { name: "mask".into(), value: "8'hff".into(), ty: None, location: None };
+    /// let value = p.const_value().unwrap();
+    /// assert_eq!(value, ConstValue::integer(8, false, 255));
+    /// assert_eq!(u64::try_from(&value).unwrap(), 255);
+    /// ```
+    pub fn const_value(&self) -> Result<ConstValue, ConstValueError> {
+        let value = self.value.parse::<ConstValue>()?;
+        Ok(match &self.ty {
+            Some(ty) => value.with_type(ty),
+            None => value,
+        })
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub struct Package {
     pub name: String,
+    /// The package's `parameter` and `localparam` declarations.
     pub parameters: HashMap<String, Parameter>,
+    /// The package's typedefs. Types the grammar does not support are omitted.
+    pub typedefs: HashMap<String, Type>,
+    /// The values of each enum typedef in [`Package::typedefs`].
+    pub enums: HashMap<String, Vec<Variant>>,
+    /// The signatures of the package's functions and tasks.
+    pub subroutines: HashMap<String, Subroutine>,
+    /// The package imports at package scope, in source order.
+    pub imports: Vec<Import>,
+    pub location: Option<SourceLocation>,
 }
 
 impl Index<&str> for Package {
@@ -68,6 +105,39 @@ impl Index<&str> for Package {
     }
 }
 
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SubroutineKind {
+    Function,
+    Task,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Subroutine {
+    pub name: String,
+    pub kind: SubroutineKind,
+    /// The return type of a function, or `None` for a task or a void function.
+    /// Also `None` if the grammar does not support the type.
+    pub return_type: Option<Type>,
+    pub arguments: Vec<SubroutineArgument>,
+    pub location: Option<SourceLocation>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SubroutineArgument {
+    pub name: String,
+    pub dir: PortDir,
+    /// The argument's type, if the grammar supports it.
+    pub ty: Option<Type>,
+}
+
+/// A package import, e.g. `import pkg::*;` or `import pkg::item;`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Import {
+    pub package: String,
+    /// The imported name, or `None` for a wildcard import.
+    pub item: Option<String>,
+}
+
 pub fn extract_packages(
     cfg: &crate::SlangConfig,
 ) -> Result<HashMap<String, Package>, Box<dyn Error>> {
@@ -75,93 +145,262 @@ pub fn extract_packages(
 }
 
 pub fn extract_packages_from_value(value: &Value) -> HashMap<String, Package> {
-    let mut packages = HashMap::new();
-    let type_resolver = crate::extract::TypeResolver::new(value);
+    let type_resolver = TypeResolver::new(value);
+    package_nodes(value)
+        .map(|(name, package)| (name.to_string(), process_package(package, &type_resolver)))
+        .collect()
+}
 
-    if let Some(members) = value
-        .get("design")
-        .and_then(|v| v.get("members").and_then(|v| v.as_array()))
-    {
-        for member in members {
-            if let Some(kind) = member.get("kind") {
-                if kind == "CompilationUnit" {
-                    extract_packages_from_compilation_unit(member, &mut packages, &type_resolver);
+/// Returns the name and node of each package declared in the compilation
+/// units of a design.
+pub(crate) fn package_nodes(value: &Value) -> impl Iterator<Item = (&str, &Value)> {
+    value["design"]["members"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|member| member["kind"] == "CompilationUnit")
+        .flat_map(|unit| unit["members"].as_array().into_iter().flatten())
+        .filter(|member| member["kind"] == "Package")
+        .filter_map(|member| Some((member["name"].as_str()?, member)))
+}
+
+fn process_package(value: &Value, type_resolver: &TypeResolver) -> Package {
+    let mut package = Package {
+        name: value["name"].as_str().unwrap_or_default().to_string(),
+        parameters: HashMap::new(),
+        typedefs: HashMap::new(),
+        enums: HashMap::new(),
+        subroutines: HashMap::new(),
+        imports: Vec::new(),
+        location: source_location(value),
+    };
+    let Some(members) = value.get("members").and_then(|v| v.as_array()) else {
+        return package;
+    };
+    for member in members {
+        match member.get("kind").and_then(|v| v.as_str()) {
+            Some("Parameter") => {
+                if let Some(parameter) = process_parameter(member, type_resolver) {
+                    package.parameters.insert(parameter.name.clone(), parameter);
                 }
             }
-        }
-    }
-
-    packages
-}
-
-fn extract_packages_from_compilation_unit(
-    value: &Value,
-    packages: &mut HashMap<String, Package>,
-    type_resolver: &crate::extract::TypeResolver,
-) {
-    if let Some(members) = value.get("members").and_then(|v| v.as_array()) {
-        for member in members {
-            if let Some(kind) = member.get("kind") {
-                if kind == "Package" {
-                    if let Some(name) = member.get("name").and_then(|v| v.as_str()) {
-                        let mut package = Package {
-                            name: name.to_string(),
-                            parameters: HashMap::new(),
-                        };
-                        if let Some(members) = member.get("members").and_then(|v| v.as_array()) {
-                            for member in members {
-                                if let Some(kind) = member.get("kind") {
-                                    if kind == "Parameter" {
-                                        if let Some(parameter) = process_parameter(member) {
-                                            package
-                                                .parameters
-                                                .insert(parameter.name.clone(), parameter);
-                                        }
-                                    } else if kind == "TypeAlias" {
-                                        if let Some(type_alias) =
-                                            process_type_alias(member, type_resolver)
-                                        {
-                                            package
-                                                .parameters
-                                                .insert(type_alias.name.clone(), type_alias);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        packages.insert(name.to_string(), package);
+            Some("TypeAlias") => {
+                if let Some((name, ty)) = process_type_alias(member, type_resolver) {
+                    if let Type::Enum { variants, .. } = &ty {
+                        package.enums.insert(name.clone(), variants.clone());
                     }
+                    package.typedefs.insert(name, ty);
                 }
             }
+            Some("Subroutine") => {
+                if let Some(subroutine) = process_subroutine(member, type_resolver) {
+                    package
+                        .subroutines
+                        .insert(subroutine.name.clone(), subroutine);
+                }
+            }
+            Some("WildcardImport") | Some("ExplicitImport") => {
+                if let Some(package_name) = member.get("package").and_then(|v| v.as_str()) {
+                    package.imports.push(Import {
+                        package: package_name.to_string(),
+                        item: member
+                            .get("import")
+                            .and_then(|v| v.as_str())
+                            .map(str::to_string),
+                    });
+                }
+            }
+            _ => {}
         }
     }
+    package
 }
 
-fn process_parameter(member: &Value) -> Option<Parameter> {
+fn process_parameter(member: &Value, type_resolver: &TypeResolver) -> Option<Parameter> {
     if let Some(value) = member.get("value").and_then(|v| v.as_str()) {
         if let Some(name) = member.get("name").and_then(|v| v.as_str()) {
             return Some(Parameter {
                 name: name.to_string(),
                 value: value.to_string(),
+                ty: member
+                    .get("type")
+                    .and_then(|v| v.as_str())
+                    .and_then(|ty| type_resolver.build_type(ty).ok()),
+                location: source_location(member),
             });
         }
     }
     None
 }
 
-fn process_type_alias(
-    member: &Value,
-    type_resolver: &crate::extract::TypeResolver,
-) -> Option<Parameter> {
-    if let Some(target) = member.get("target").and_then(|v| v.as_str()) {
-        if let Some(name) = member.get("name").and_then(|v| v.as_str()) {
-            return Some(Parameter {
-                name: name.to_string(),
-                value: type_resolver
-                    .resolve_alias(member, name)
-                    .unwrap_or_else(|| type_resolver.resolve(target)),
-            });
-        }
+fn process_type_alias(member: &Value, type_resolver: &TypeResolver) -> Option<(String, Type)> {
+    let target = member.get("target").and_then(|v| v.as_str())?;
+    let name = member.get("name").and_then(|v| v.as_str())?;
+    let type_text = type_resolver
+        .resolve_alias(member, name)
+        .unwrap_or_else(|| type_resolver.resolve(target));
+    let ty = parse_resolved_type(&type_text).ok()?;
+    Some((name.to_string(), ty))
+}
+
+fn process_subroutine(member: &Value, type_resolver: &TypeResolver) -> Option<Subroutine> {
+    let name = member.get("name").and_then(|v| v.as_str())?;
+    let kind = match member.get("subroutineKind").and_then(|v| v.as_str()) {
+        Some("Task") => SubroutineKind::Task,
+        _ => SubroutineKind::Function,
+    };
+    let return_type = match member.get("returnType").and_then(|v| v.as_str()) {
+        Some("void") | None => None,
+        Some(ty) => type_resolver.build_type(ty).ok(),
+    };
+
+    let arguments = member
+        .get("members")
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter(|argument| argument["kind"] == "FormalArgument")
+        .filter_map(|argument| {
+            Some(SubroutineArgument {
+                name: argument["name"].as_str()?.to_string(),
+                dir: argument["direction"].as_str()?.parse().ok()?,
+                ty: argument["type"]
+                    .as_str()
+                    .and_then(|ty| type_resolver.build_type(ty).ok()),
+            })
+        })
+        .collect();
+
+    Some(Subroutine {
+        name: name.to_string(),
+        kind,
+        return_type,
+        arguments,
+        location: source_location(member),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_type_definition;
+    use serde_json::json;
+
+    #[test]
+    fn extracts_typed_package_contents() {
+        let ast = json!({
+            "design": {
+                "members": [{
+                    "kind": "CompilationUnit",
+                    "members": [{
+                        "kind": "Package",
+                        "name": "pkg",
+                        "members": [
+                            { "kind": "WildcardImport", "name": "", "package": "base_pkg" },
+                            {
+                                "kind": "ExplicitImport",
+                                "name": "W",
+                                "package": "base_pkg",
+                                "import": "W"
+                            },
+                            {
+                                "kind": "Parameter",
+                                "name": "MASK",
+                                "type": "logic[7:0]",
+                                "value": "8'hff",
+                                "isLocal": true
+                            },
+                            {
+                                "kind": "TypeAlias",
+                                "name": "state_t",
+                                "target": "enum{IDLE=2'd0,BUSY=2'd1}state_t"
+                            },
+                            {
+                                "kind": "Subroutine",
+                                "name": "inc",
+                                "returnType": "logic[7:0]",
+                                "subroutineKind": "Function",
+                                "members": [
+                                    {
+                                        "kind": "FormalArgument",
+                                        "name": "x",
+                                        "type": "logic[7:0]",
+                                        "direction": "In"
+                                    },
+                                    { "kind": "Variable", "name": "tmp", "type": "int" }
+                                ]
+                            },
+                            {
+                                "kind": "Subroutine",
+                                "name": "wait_cycles",
+                                "returnType": "void",
+                                "subroutineKind": "Task",
+                                "members": [
+                                    {
+                                        "kind": "FormalArgument",
+                                        "name": "n",
+                                        "type": "int",
+                                        "direction": "Ref"
+                                    }
+                                ]
+                            }
+                        ]
+                    }]
+                }]
+            }
+        });
+
+        let packages = extract_packages_from_value(&ast);
+        let pkg = &packages["pkg"];
+
+        assert_eq!(
+            pkg.imports,
+            vec![
+                Import {
+                    package: "base_pkg".to_string(),
+                    item: None,
+                },
+                Import {
+                    package: "base_pkg".to_string(),
+                    item: Some("W".to_string()),
+                },
+            ]
+        );
+
+        assert_eq!(
+            pkg["MASK"].ty,
+            Some(parse_type_definition("logic[7:0]").unwrap())
+        );
+        assert_eq!(
+            pkg["MASK"].const_value().unwrap(),
+            ConstValue::integer(8, false, 255)
+        );
+
+        assert_eq!(pkg.typedefs["state_t"].width().unwrap(), 2);
+        let names: Vec<&str> = pkg.enums["state_t"]
+            .iter()
+            .map(|variant| variant.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["IDLE", "BUSY"]);
+
+        assert_eq!(
+            pkg.subroutines["inc"],
+            Subroutine {
+                name: "inc".to_string(),
+                kind: SubroutineKind::Function,
+                return_type: Some(parse_type_definition("logic[7:0]").unwrap()),
+                arguments: vec![SubroutineArgument {
+                    name: "x".to_string(),
+                    dir: PortDir::Input,
+                    ty: Some(parse_type_definition("logic[7:0]").unwrap()),
+                }],
+                location: None,
+            }
+        );
+        let task = &pkg.subroutines["wait_cycles"];
+        assert_eq!(task.kind, SubroutineKind::Task);
+        assert_eq!(task.return_type, None);
+        assert_eq!(task.arguments[0].dir, PortDir::Ref);
     }
-    None
 }
@@ -0,0 +1,338 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The dependencies between the packages of a design, for ordering source
+//! files so that every package is compiled before its first use.
+
+use crate::location::source_location;
+use crate::package::package_nodes;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+/// The packages of a design and the packages each of them depends on.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct PackageGraph {
+    /// For each package, the other packages it imports or refers to with a
+    /// qualified name such as `other_pkg::T`.
+    pub dependencies: HashMap<String, BTreeSet<String>>,
+    /// The file declaring each package. Only known when Slang is run with
+    /// `SlangConfig::include_source_info` set.
+    pub files: HashMap<String, String>,
+}
+
+/// A set of packages or files that depend on each other in a loop. The first
+/// entry is repeated at the end, e.g. `["a", "b", "a"]`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DependencyCycle {
+    pub cycle: Vec<String>,
+}
+
+impl fmt::Display for DependencyCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Dependency cycle: {}", self.cycle.join(" -> "))
+    }
+}
+
+impl Error for DependencyCycle {}
+
+impl PackageGraph {
+    /// Returns every package after the packages it depends on. Packages that
+    /// do not depend on each other are ordered by name.
+    pub fn package_order(&self) -> Result<Vec<String>, DependencyCycle> {
+        topological_order(&self.dependencies)
+    }
+
+    /// Returns the files declaring packages, each after the files declaring
+    /// the packages it depends on.
+    pub fn file_order(&self) -> Result<Vec<String>, DependencyCycle> {
+        let mut dependencies: HashMap<String, BTreeSet<String>> = HashMap::new();
+        for (package, file) in &self.files {
+            let file_dependencies = dependencies.entry(file.clone()).or_default();
+            for dependency in self.dependencies.get(package).into_iter().flatten() {
+                if let Some(dependency_file) = self.files.get(dependency) {
+                    if dependency_file != file {
+                        file_dependencies.insert(dependency_file.clone());
+                    }
+                }
+            }
+        }
+        topological_order(&dependencies)
+    }
+}
+
+pub fn extract_package_graph(cfg: &crate::SlangConfig) -> Result<PackageGraph, Box<dyn Error>> {
+    let cfg = crate::SlangConfig {
+        include_source_info: true,
+        ..*cfg
+    };
+    Ok(extract_package_graph_from_value(&crate::run_slang(&cfg)?))
+}
+
+/// Builds the package graph from Slang's AST. A package depends on another if
+/// it imports it, or if any of its members refers to a symbol declared there,
+/// whether through a qualified name or a type or value link.
+pub fn extract_package_graph_from_value(value: &Value) -> PackageGraph {
+    let packages: Vec<(&str, &Value)> = package_nodes(value).collect();
+
+    let mut owners = HashMap::new();
+    for (name, package) in &packages {
+        collect_addresses(package, name, &mut owners);
+    }
+
+    let link_pattern = Regex::new(r"\b([0-9]+) [A-Za-z_]").unwrap();
+    let qualified_pattern = Regex::new(r"([A-Za-z_][A-Za-z0-9_$]*)::").unwrap();
+    let mut graph = PackageGraph::default();
+    for (name, package) in &packages {
+        let mut references = BTreeSet::new();
+        collect_references(package, &mut |text| {
+            for captures in link_pattern.captures_iter(text) {
+                if let Some(owner) = captures[1].parse::<u64>().ok().and_then(|a| owners.get(&a)) {
+                    references.insert(owner.to_string());
+                }
+            }
+            for captures in qualified_pattern.captures_iter(text) {
+                references.insert(captures[1].to_string());
+            }
+        });
+        if let Some(imports) = package["members"].as_array() {
+            for import in imports {
+                if import["kind"] == "WildcardImport" || import["kind"] == "ExplicitImport" {
+                    if let Some(imported) = import["package"].as_str() {
+                        references.insert(imported.to_string());
+                    }
+                }
+            }
+        }
+        references.retain(|reference| {
+            reference != name && packages.iter().any(|(other, _)| other == reference)
+        });
+        graph.dependencies.insert(name.to_string(), references);
+
+        if let Some(location) = source_location(package) {
+            graph.files.insert(name.to_string(), location.file);
+        }
+    }
+    graph
+}
+
+/// Returns the sources of `cfg` reordered so that the files declaring packages
+/// come first, each after the files it depends on. The other sources follow
+/// in their original order.
+pub fn compile_order(cfg: &crate::SlangConfig) -> Result<Vec<String>, Box<dyn Error>> {
+    let package_files = extract_package_graph(cfg)?.file_order()?;
+    let mut ordered: Vec<String> = Vec::new();
+    for file in &package_files {
+        if let Some(source) = cfg.sources.iter().find(|source| same_file(source, file)) {
+            if !ordered.iter().any(|s| s == source) {
+                ordered.push(source.to_string());
+            }
+        }
+    }
+    for source in cfg.sources {
+        if !ordered.iter().any(|s| s == source) {
+            ordered.push(source.to_string());
+        }
+    }
+    Ok(ordered)
+}
+
+/// Whether two paths name the same file, as Slang may report a path that is
+/// spelled differently from the one it was given.
+fn same_file(a: &str, b: &str) -> bool {
+    a == b
+        || match (Path::new(a).canonicalize(), Path::new(b).canonicalize()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+}
+
+/// Records the package owning each symbol address within `value`.
+fn collect_addresses<'a>(value: &Value, package: &'a str, owners: &mut HashMap<u64, &'a str>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(address) = object.get("addr").and_then(Value::as_u64) {
+                owners.insert(address, package);
+            }
+            for child in object.values() {
+                collect_addresses(child, package, owners);
+            }
+        }
+        Value::Array(array) => {
+            for child in array {
+                collect_addresses(child, package, owners);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Calls `visit` with every string within `value`.
+fn collect_references(value: &Value, visit: &mut impl FnMut(&str)) {
+    match value {
+        Value::String(text) => visit(text),
+        Value::Object(object) => {
+            for child in object.values() {
+                collect_references(child, visit);
+            }
+        }
+        Value::Array(array) => {
+            for child in array {
+                collect_references(child, visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Orders the nodes of `graph` so that each comes after its dependencies,
+/// visiting nodes and dependencies by name for a deterministic result.
+/// Dependencies that are not nodes of the graph are ignored.
+fn topological_order(
+    graph: &HashMap<String, BTreeSet<String>>,
+) -> Result<Vec<String>, DependencyCycle> {
+    fn visit<'a>(
+        node: &'a str,
+        graph: &'a HashMap<String, BTreeSet<String>>,
+        done: &mut BTreeSet<&'a str>,
+        stack: &mut Vec<&'a str>,
+        order: &mut Vec<String>,
+    ) -> Result<(), DependencyCycle> {
+        if done.contains(node) {
+            return Ok(());
+        }
+        if let Some(start) = stack.iter().position(|&n| n == node) {
+            let mut cycle: Vec<String> = stack[start..].iter().map(|n| n.to_string()).collect();
+            cycle.push(node.to_string());
+            return Err(DependencyCycle { cycle });
+        }
+        stack.push(node);
+        for dependency in &graph[node] {
+            if graph.contains_key(dependency) {
+                visit(dependency, graph, done, stack, order)?;
+            }
+        }
+        stack.pop();
+        done.insert(node);
+        order.push(node.to_string());
+        Ok(())
+    }
+
+    let mut nodes: Vec<&String> = graph.keys().collect();
+    nodes.sort();
+    let mut done = BTreeSet::new();
+    let mut order = Vec::new();
+    for node in nodes {
+        visit(node, graph, &mut done, &mut Vec::new(), &mut order)?;
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn design(packages: Vec<Value>) -> Value {
+        json!({
+            "design": {
+                "members": [{ "kind": "CompilationUnit", "members": packages }]
+            }
+        })
+    }
+
+    #[test]
+    fn orders_packages_after_their_dependencies() {
+        let ast = design(vec![
+            json!({
+                "kind": "Package",
+                "name": "top_pkg",
+                "source_file": "top_pkg.sv",
+                "source_line": 1,
+                "source_column": 9,
+                "members": [
+                    { "kind": "WildcardImport", "name": "", "package": "mid_pkg" },
+                    {
+                        "kind": "Parameter",
+                        "name": "N",
+                        "type": "int",
+                        "value": "4",
+                        "initializer": {
+                            "kind": "NamedValue",
+                            "symbol": "10 W",
+                            "type": "int"
+                        }
+                    }
+                ]
+            }),
+            json!({
+                "kind": "Package",
+                "name": "mid_pkg",
+                "source_file": "mid_pkg.sv",
+                "source_line": 1,
+                "source_column": 9,
+                "members": [
+                    { "kind": "TypeAlias", "name": "t", "target": "base_pkg::word_t" }
+                ]
+            }),
+            json!({
+                "kind": "Package",
+                "name": "base_pkg",
+                "source_file": "base_pkg.sv",
+                "source_line": 1,
+                "source_column": 9,
+                "members": [
+                    { "kind": "Parameter", "name": "W", "addr": 10, "type": "int", "value": "4" },
+                    { "kind": "TypeAlias", "name": "word_t", "target": "logic[3:0]" }
+                ]
+            }),
+        ]);
+
+        let graph = extract_package_graph_from_value(&ast);
+        assert_eq!(
+            graph.dependencies["top_pkg"],
+            BTreeSet::from(["base_pkg".to_string(), "mid_pkg".to_string()])
+        );
+        assert_eq!(
+            graph.dependencies["mid_pkg"],
+            BTreeSet::from(["base_pkg".to_string()])
+        );
+        assert!(graph.dependencies["base_pkg"].is_empty());
+
+        assert_eq!(
+            graph.package_order().unwrap(),
+            vec!["base_pkg", "mid_pkg", "top_pkg"]
+        );
+        assert_eq!(
+            graph.file_order().unwrap(),
+            vec!["base_pkg.sv", "mid_pkg.sv", "top_pkg.sv"]
+        );
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let ast = design(vec![
+            json!({
+                "kind": "Package",
+                "name": "a_pkg",
+                "members": [{ "kind": "WildcardImport", "name": "", "package": "b_pkg" }]
+            }),
+            json!({
+                "kind": "Package",
+                "name": "b_pkg",
+                "members": [{ "kind": "TypeAlias", "name": "t", "target": "a_pkg::t" }]
+            }),
+        ]);
+
+        let error = extract_package_graph_from_value(&ast)
+            .package_order()
+            .unwrap_err();
+        assert_eq!(error.cycle, vec!["a_pkg", "b_pkg", "a_pkg"]);
+        assert_eq!(
+            error.to_string(),
+            "Dependency cycle: a_pkg -> b_pkg -> a_pkg"
+        );
+    }
+}
@@ -0,0 +1,402 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Detection of hierarchical references (XMRs), such as `top.u_a.sig` used
+//! from another scope, which partitioned synthesis and emulation flows cannot
+//! follow across partition boundaries.
+
+use crate::expression::{link_address, link_name};
+use crate::extract::TypeResolver;
+use crate::hierarchy::{hierarchy_with_bodies, instantiated_scopes};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+
+/// One hierarchical reference of the design.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HierarchicalReference {
+    /// The instance, or generate block within one, whose code contains the
+    /// reference, e.g. `top.u_tb.g[0]`.
+    pub source_scope: String,
+    /// The full hierarchical path of the referenced symbol, or just its name
+    /// if it is not declared in any instance of the design.
+    pub target_path: String,
+    /// The kind of the referenced symbol as named by Slang, e.g. `Variable`,
+    /// `Net` or `Parameter`, if it was found.
+    pub target_kind: Option<String>,
+    /// How the reference crosses instance boundaries, if its target was
+    /// found.
+    pub direction: Option<ReferenceDirection>,
+}
+
+/// Where the target of a hierarchical reference lies relative to the
+/// instance making it. Generate blocks are not instance boundaries.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ReferenceDirection {
+    /// The target is in the same instance, e.g. in one of its generate
+    /// blocks.
+    Local,
+    /// The target is in an instance above the source.
+    Upward,
+    /// The target is in an instance below the source.
+    Downward,
+    /// The target is in another branch of the hierarchy, reached by going up
+    /// to a common ancestor and down again.
+    Sideways,
+}
+
+impl HierarchicalReference {
+    /// Whether the reference leaves the instance that makes it. A reference
+    /// whose target was not found is assumed to.
+    pub fn crosses_instances(&self) -> bool {
+        self.direction != Some(ReferenceDirection::Local)
+    }
+}
+
+pub fn extract_hierarchical_references(
+    cfg: &crate::SlangConfig,
+) -> Result<Vec<HierarchicalReference>, Box<dyn Error>> {
+    Ok(extract_hierarchical_references_from_value(
+        &crate::run_slang(cfg)?,
+    ))
+}
+
+/// Walks every expression of the elaborated design, including port
+/// connections, and reports its hierarchical references. Instances are
+/// visited depth-first, each with its own generate blocks before the
+/// instances below it. A reference made several times from the same scope
+/// is reported once.
+pub fn extract_hierarchical_references_from_value(value: &Value) -> Vec<HierarchicalReference> {
+    let type_resolver = TypeResolver::new(value);
+    let (hierarchy, bodies) = hierarchy_with_bodies(value);
+    let mut scopes = Vec::new();
+    for id in hierarchy.depth_first() {
+        let Some(body) = bodies[id.index()] else {
+            continue;
+        };
+        let path = hierarchy.path(id);
+        for (scope, hier_prefix) in instantiated_scopes(body) {
+            scopes.push(Scope {
+                value: scope,
+                path: format!("{path}{hier_prefix}"),
+                instance: path.clone(),
+            });
+        }
+    }
+
+    let mut symbols: HashMap<u64, Vec<Symbol>> = HashMap::new();
+    for scope in &scopes {
+        index_symbols(
+            &type_resolver,
+            scope.value,
+            &scope.path,
+            &scope.instance,
+            &mut symbols,
+        );
+    }
+
+    let mut references: Vec<HierarchicalReference> = Vec::new();
+    for scope in &scopes {
+        let mut links = Vec::new();
+        for member in scope.value["members"].as_array().into_iter().flatten() {
+            connection_or_member_links(&type_resolver, member, &mut links);
+        }
+        for link in links {
+            let target = link_address(link)
+                .and_then(|address| symbols.get(&address))
+                .and_then(|candidates| closest(candidates, &scope.path));
+            let reference = match target {
+                Some(target) => HierarchicalReference {
+                    source_scope: scope.path.clone(),
+                    target_path: target.path.clone(),
+                    target_kind: Some(target.kind.clone()),
+                    direction: Some(direction(&scope.instance, &target.instance)),
+                },
+                None => HierarchicalReference {
+                    source_scope: scope.path.clone(),
+                    target_path: link_name(link).unwrap_or_default().to_string(),
+                    target_kind: None,
+                    direction: None,
+                },
+            };
+            if !references.contains(&reference) {
+                references.push(reference);
+            }
+        }
+    }
+    references
+}
+
+/// An instance body or generate block of the design.
+struct Scope<'a> {
+    value: &'a Value,
+    path: String,
+    /// The path of the instance the scope belongs to.
+    instance: String,
+}
+
+/// A symbol declared in a scope of the design.
+struct Symbol {
+    path: String,
+    instance: String,
+    kind: String,
+}
+
+/// Records the symbols declared in `scope` and in the tasks, functions and
+/// named blocks nested in it. Instances and generate blocks are scopes of
+/// their own and are indexed separately.
+fn index_symbols(
+    type_resolver: &TypeResolver,
+    scope: &Value,
+    path: &str,
+    instance: &str,
+    symbols: &mut HashMap<u64, Vec<Symbol>>,
+) {
+    for member in scope["members"].as_array().into_iter().flatten() {
+        let member = type_resolver.resolve_node(member);
+        let Some(name) = member["name"].as_str().filter(|name| !name.is_empty()) else {
+            continue;
+        };
+        let member_path = format!("{path}.{name}");
+        if let Some(address) = member["addr"].as_u64() {
+            symbols.entry(address).or_default().push(Symbol {
+                path: member_path.clone(),
+                instance: instance.to_string(),
+                kind: member["kind"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+        match member["kind"].as_str() {
+            Some("Instance")
+            | Some("InstanceArray")
+            | Some("CheckerInstance")
+            | Some("GenerateBlock")
+            | Some("GenerateBlockArray")
+            | Some("UninstantiatedDef") => {}
+            _ => index_symbols(type_resolver, member, &member_path, instance, symbols),
+        }
+    }
+}
+
+/// Collects the hierarchical references a scope member makes in its own
+/// scope. The bodies of instances are scopes of their own, so only their
+/// port connections count, and nested generate blocks are visited
+/// separately.
+fn connection_or_member_links<'a>(
+    type_resolver: &TypeResolver<'a>,
+    member: &'a Value,
+    links: &mut Vec<&'a Value>,
+) {
+    let member = type_resolver.resolve_node(member);
+    match member["kind"].as_str() {
+        Some("Instance") | Some("CheckerInstance") => {
+            hierarchical_links(&member["connections"], links)
+        }
+        Some("InstanceArray") => {
+            for element in member["members"].as_array().into_iter().flatten() {
+                connection_or_member_links(type_resolver, element, links);
+            }
+        }
+        Some("GenerateBlock") | Some("GenerateBlockArray") | Some("UninstantiatedDef") => {}
+        _ => hierarchical_links(member, links),
+    }
+}
+
+/// Collects the symbol links of the hierarchical references within `value`.
+fn hierarchical_links<'a>(value: &'a Value, links: &mut Vec<&'a Value>) {
+    match value {
+        Value::Object(object) => {
+            if value["kind"] == "HierarchicalValue" {
+                links.push(&value["symbol"]);
+            }
+            for child in object.values() {
+                hierarchical_links(child, links);
+            }
+        }
+        Value::Array(array) => {
+            for child in array {
+                hierarchical_links(child, links);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Picks the declaration of a symbol nearest to the referencing scope.
+/// Slang shares one body between identical instances, so a symbol address
+/// may stand for a declaration in each of them.
+fn closest<'a>(candidates: &'a [Symbol], scope: &str) -> Option<&'a Symbol> {
+    let common_prefix = |symbol: &Symbol| {
+        symbol
+            .path
+            .split('.')
+            .zip(scope.split('.'))
+            .take_while(|(a, b)| a == b)
+            .count()
+    };
+    candidates
+        .iter()
+        .rev()
+        .max_by_key(|symbol| common_prefix(symbol))
+}
+
+fn direction(source: &str, target: &str) -> ReferenceDirection {
+    let is_below = |path: &str, ancestor: &str| {
+        path.strip_prefix(ancestor)
+            .is_some_and(|rest| rest.starts_with('.'))
+    };
+    if source == target {
+        ReferenceDirection::Local
+    } else if is_below(source, target) {
+        ReferenceDirection::Upward
+    } else if is_below(target, source) {
+        ReferenceDirection::Downward
+    } else {
+        ReferenceDirection::Sideways
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_hierarchical_references() {
+        let ast = json!({
+            "design": {
+                "members": [{
+                    "name": "top",
+                    "kind": "Instance",
+                    "body": {
+                        "name": "top",
+                        "kind": "InstanceBody",
+                        "members": [
+                            { "name": "mode", "kind": "Variable", "addr": 1, "type": "logic" },
+                            {
+                                "name": "dump",
+                                "kind": "Subroutine",
+                                "addr": 7,
+                                "members": [
+                                    { "name": "count", "kind": "Variable", "addr": 8, "type": "int" }
+                                ]
+                            },
+                            {
+                                "name": "u_dut",
+                                "kind": "Instance",
+                                "addr": 2,
+                                "body": {
+                                    "name": "dut",
+                                    "kind": "InstanceBody",
+                                    "members": [
+                                        { "name": "state", "kind": "Variable", "addr": 3, "type": "logic" },
+                                        {
+                                            "name": "",
+                                            "kind": "ContinuousAssign",
+                                            "assignment": {
+                                                "kind": "Assignment",
+                                                "left": { "kind": "NamedValue", "symbol": "3 state" },
+                                                "right": { "kind": "HierarchicalValue", "symbol": "1 mode" }
+                                            }
+                                        }
+                                    ]
+                                }
+                            },
+                            {
+                                "name": "u_mon",
+                                "kind": "Instance",
+                                "body": {
+                                    "name": "mon",
+                                    "kind": "InstanceBody",
+                                    "members": [{
+                                        "name": "",
+                                        "kind": "ProceduralBlock",
+                                        "procedureKind": "Always",
+                                        "body": {
+                                            "kind": "ExpressionStatement",
+                                            "expr": {
+                                                "kind": "Call",
+                                                "arguments": [
+                                                    { "kind": "HierarchicalValue", "symbol": "3 state" },
+                                                    { "kind": "HierarchicalValue", "symbol": "3 state" },
+                                                    { "kind": "HierarchicalValue", "symbol": "9 gone" },
+                                                    { "kind": "HierarchicalValue", "symbol": "8 count" }
+                                                ]
+                                            }
+                                        }
+                                    }]
+                                }
+                            },
+                            {
+                                "name": "u_arr",
+                                "kind": "InstanceArray",
+                                "range": "[0:0]",
+                                "members": [{
+                                    "name": "",
+                                    "kind": "Instance",
+                                    "body": {
+                                        "name": "probe",
+                                        "kind": "InstanceBody",
+                                        "members": [{
+                                            "name": "",
+                                            "kind": "ContinuousAssign",
+                                            "assignment": {
+                                                "kind": "Assignment",
+                                                "left": { "kind": "NamedValue", "symbol": "5 q" },
+                                                "right": { "kind": "HierarchicalValue", "symbol": "1 mode" }
+                                            }
+                                        }]
+                                    },
+                                    "connections": [{
+                                        "port": "6 d",
+                                        "expr": { "kind": "HierarchicalValue", "symbol": "3 state" }
+                                    }]
+                                }]
+                            },
+                            {
+                                "name": "g",
+                                "kind": "GenerateBlock",
+                                "constructIndex": 1,
+                                "isUninstantiated": false,
+                                "members": [{
+                                    "name": "u_sink",
+                                    "kind": "Instance",
+                                    "body": { "name": "sink", "kind": "InstanceBody" },
+                                    "connections": [{
+                                        "port": "4 d",
+                                        "expr": { "kind": "HierarchicalValue", "symbol": "3 state" }
+                                    }]
+                                }]
+                            }
+                        ]
+                    }
+                }]
+            }
+        });
+
+        let references = extract_hierarchical_references_from_value(&ast);
+        let reference = |source: &str, target: &str, direction| HierarchicalReference {
+            source_scope: source.to_string(),
+            target_path: target.to_string(),
+            target_kind: Some("Variable".to_string()),
+            direction: Some(direction),
+        };
+        assert_eq!(
+            references,
+            vec![
+                reference("top", "top.u_dut.state", ReferenceDirection::Downward),
+                reference("top.g", "top.u_dut.state", ReferenceDirection::Downward),
+                reference("top.u_dut", "top.mode", ReferenceDirection::Upward),
+                reference("top.u_mon", "top.u_dut.state", ReferenceDirection::Sideways),
+                HierarchicalReference {
+                    source_scope: "top.u_mon".to_string(),
+                    target_path: "gone".to_string(),
+                    target_kind: None,
+                    direction: None,
+                },
+                reference("top.u_mon", "top.dump.count", ReferenceDirection::Upward),
+                reference("top.u_arr[0]", "top.mode", ReferenceDirection::Upward),
+            ]
+        );
+        assert!(references[0].crosses_instances());
+        assert!(references[4].crosses_instances());
+    }
+}
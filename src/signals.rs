@@ -0,0 +1,366 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Extraction of the nets and variables declared in every instance body of
+//! the elaborated design, with how each of them is driven.
+
+use crate::attributes::{Attribute, extract_attributes};
+use crate::expression::{link_address, link_name};
+use crate::extract::{MemberIter, Type, TypeResolver};
+use crate::hierarchy::{hierarchy_with_bodies, instantiated_scopes};
+use crate::location::{SourceLocation, source_location};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+
+/// A net or variable declared in an instance body.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Signal {
+    pub name: String,
+    /// The generate blocks between the instance body and the declaration,
+    /// e.g. `.g[0].genblk1`, or an empty string.
+    pub hier_prefix: String,
+    /// The signal's type, including its unpacked dimensions.
+    pub ty: Type,
+    pub kind: SignalKind,
+    /// The number of elements of an unpacked array, or `None` for a signal
+    /// that is not one.
+    pub unpacked_size: Option<usize>,
+    /// Whether the signal is the internal net or variable of a port.
+    pub is_port: bool,
+    /// The kinds of process that assign to the signal, in the order first
+    /// found in the body. Drivers outside the body, such as the output ports
+    /// of child instances, are not included.
+    pub drivers: Vec<SignalDriver>,
+    pub location: Option<SourceLocation>,
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SignalKind {
+    /// A net, with its net type such as `wire`, `tri`, `wand` or a
+    /// user-defined nettype.
+    Net {
+        net_type: String,
+    },
+    Variable,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SignalDriver {
+    AlwaysFf,
+    AlwaysComb,
+    AlwaysLatch,
+    /// A plain `always` block.
+    Always,
+    /// A continuous assignment, including the initializer of a net
+    /// declaration (`wire a = b;`).
+    ContinuousAssign,
+}
+
+/// The signals of a design.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SignalInventory {
+    /// The signals of each definition, as elaborated for its first instance
+    /// in design order. Instances with other parameter values may declare
+    /// signals of other types; see `instances` for those.
+    pub definitions: HashMap<String, Vec<Signal>>,
+    /// The signals of each instance, keyed by its full hierarchical path,
+    /// e.g. `top.u_core.genblk1.u_fifo`.
+    pub instances: HashMap<String, Vec<Signal>>,
+}
+
+pub fn extract_signals(
+    cfg: &crate::SlangConfig,
+    skip_unsupported: bool,
+) -> Result<SignalInventory, Box<dyn Error>> {
+    Ok(extract_signals_from_value(
+        &crate::run_slang(cfg)?,
+        skip_unsupported,
+    ))
+}
+
+/// Walks the whole design and lists the nets and variables of every
+/// instance body, including those declared in generate blocks. Signals of
+/// types the grammar does not support are omitted if `skip_unsupported` is
+/// set, and cause a panic otherwise.
+pub fn extract_signals_from_value(value: &Value, skip_unsupported: bool) -> SignalInventory {
+    let type_resolver = TypeResolver::new(value);
+    let (hierarchy, bodies) = hierarchy_with_bodies(value);
+    let mut inventory = SignalInventory::default();
+    for id in hierarchy.depth_first() {
+        let Some(body) = bodies[id.index()] else {
+            continue;
+        };
+        let signals = body_signals(&type_resolver, body, skip_unsupported);
+        inventory
+            .definitions
+            .entry(hierarchy[id].def_name.clone())
+            .or_insert_with(|| signals.clone());
+        inventory.instances.insert(hierarchy.path(id), signals);
+    }
+    inventory
+}
+
+/// Extracts the nets and variables declared by one instance body and the
+/// generate blocks within it.
+fn body_signals(type_resolver: &TypeResolver, body: &Value, skip_unsupported: bool) -> Vec<Signal> {
+    let scopes = instantiated_scopes(body);
+
+    let mut port_symbols = Vec::new();
+    let mut drivers: Vec<(&Value, SignalDriver)> = Vec::new();
+    for (scope, _) in &scopes {
+        for port in MemberIter::new(scope, &["Port"]) {
+            port_symbols.push(&port["internalSymbol"]);
+        }
+        for process in MemberIter::new(scope, &["ProceduralBlock", "ContinuousAssign"]) {
+            let driver = match (process["kind"].as_str(), process["procedureKind"].as_str()) {
+                (Some("ContinuousAssign"), _) => SignalDriver::ContinuousAssign,
+                (_, Some("AlwaysFF")) => SignalDriver::AlwaysFf,
+                (_, Some("AlwaysComb")) => SignalDriver::AlwaysComb,
+                (_, Some("AlwaysLatch")) => SignalDriver::AlwaysLatch,
+                (_, Some("Always")) => SignalDriver::Always,
+                _ => continue,
+            };
+            let mut targets = Vec::new();
+            assignment_targets(process, &mut targets);
+            drivers.extend(targets.into_iter().map(|target| (target, driver)));
+        }
+    }
+
+    let mut signals = Vec::new();
+    for (scope, hier_prefix) in &scopes {
+        for member in MemberIter::new(scope, &["Net", "Variable"]) {
+            let name = member["name"].as_str().unwrap_or_default();
+            if name.is_empty() {
+                continue;
+            }
+            let ty = match type_resolver.build_type(member["type"].as_str().unwrap_or_default()) {
+                Ok(ty) => ty,
+                Err(e) => {
+                    if skip_unsupported {
+                        continue;
+                    } else {
+                        panic!("{}", e);
+                    }
+                }
+            };
+            let kind = match member["kind"].as_str() {
+                Some("Net") => SignalKind::Net {
+                    net_type: link_name(&member["netType"]).unwrap_or("wire").to_string(),
+                },
+                _ => SignalKind::Variable,
+            };
+
+            let refers_to_member = |link: &Value| match (link, link_address(link)) {
+                (Value::Object(_), _) => std::ptr::eq(link, member),
+                (_, Some(address)) => member["addr"].as_u64() == Some(address),
+                _ => link_name(link) == Some(name),
+            };
+            let mut signal_drivers = Vec::new();
+            if matches!(kind, SignalKind::Net { .. }) && !member["initializer"].is_null() {
+                signal_drivers.push(SignalDriver::ContinuousAssign);
+            }
+            for (target, driver) in &drivers {
+                if refers_to_member(target) && !signal_drivers.contains(driver) {
+                    signal_drivers.push(*driver);
+                }
+            }
+
+            let dimensions = ty.unpacked_dimensions();
+            let unpacked_size = (!dimensions.is_empty()).then(|| {
+                dimensions
+                    .iter()
+                    .map(|range| (range.msb - range.lsb).unsigned_abs() as usize + 1)
+                    .product()
+            });
+            signals.push(Signal {
+                name: name.to_string(),
+                hier_prefix: hier_prefix.clone(),
+                is_port: port_symbols.iter().any(|link| refers_to_member(link)),
+                ty,
+                kind,
+                unpacked_size,
+                drivers: signal_drivers,
+                location: source_location(member),
+                attributes: extract_attributes(member),
+            });
+        }
+    }
+    signals
+}
+
+/// Collects the symbol links on the left-hand side of every assignment
+/// within `value`.
+fn assignment_targets<'a>(value: &'a Value, targets: &mut Vec<&'a Value>) {
+    match value {
+        Value::Object(object) => {
+            if value["kind"] == "Assignment" {
+                lvalue_symbols(&value["left"], targets);
+            }
+            for child in object.values() {
+                assignment_targets(child, targets);
+            }
+        }
+        Value::Array(array) => {
+            for child in array {
+                assignment_targets(child, targets);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collects the symbols that an assignment to `expr` writes to, looking
+/// through selects, member accesses and concatenations.
+fn lvalue_symbols<'a>(expr: &'a Value, targets: &mut Vec<&'a Value>) {
+    match expr["kind"].as_str() {
+        Some("NamedValue") => targets.push(&expr["symbol"]),
+        Some("ElementSelect") | Some("RangeSelect") | Some("MemberAccess") => {
+            lvalue_symbols(&expr["value"], targets)
+        }
+        Some("Concatenation") => {
+            for operand in expr["operands"].as_array().into_iter().flatten() {
+                lvalue_symbols(operand, targets);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn lists_signals_and_drivers() {
+        let leaf = json!({
+            "name": "leaf",
+            "kind": "InstanceBody",
+            "members": [
+                {
+                    "name": "q",
+                    "kind": "Port",
+                    "type": "logic",
+                    "direction": "Out",
+                    "internalSymbol": "10 q"
+                },
+                { "name": "q", "kind": "Variable", "addr": 10, "type": "logic" },
+                { "name": "mem", "kind": "Variable", "addr": 11, "type": "logic[7:0]$[0:3]" },
+                {
+                    "name": "ready",
+                    "kind": "Net",
+                    "addr": 12,
+                    "type": "logic",
+                    "netType": "1 wire",
+                    "initializer": { "kind": "NamedValue", "symbol": "10 q" }
+                },
+                {
+                    "name": "",
+                    "kind": "ProceduralBlock",
+                    "procedureKind": "AlwaysFF",
+                    "body": {
+                        "kind": "Timed",
+                        "stmt": {
+                            "kind": "ExpressionStatement",
+                            "expr": {
+                                "kind": "Assignment",
+                                "isNonBlocking": true,
+                                "left": {
+                                    "kind": "ElementSelect",
+                                    "value": { "kind": "NamedValue", "symbol": "11 mem" },
+                                    "selector": { "kind": "IntegerLiteral", "constant": "0" }
+                                },
+                                "right": { "kind": "NamedValue", "symbol": "10 q" }
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "g",
+                    "kind": "GenerateBlock",
+                    "constructIndex": 1,
+                    "isUninstantiated": false,
+                    "members": [
+                        { "name": "t", "kind": "Variable", "addr": 13, "type": "logic" },
+                        {
+                            "name": "",
+                            "kind": "ContinuousAssign",
+                            "assignment": {
+                                "kind": "Assignment",
+                                "left": {
+                                    "kind": "Concatenation",
+                                    "operands": [
+                                        { "kind": "NamedValue", "symbol": "10 q" },
+                                        { "kind": "NamedValue", "symbol": "13 t" }
+                                    ]
+                                },
+                                "right": { "kind": "NamedValue", "symbol": "12 ready" }
+                            }
+                        }
+                    ]
+                }
+            ]
+        });
+        let ast = json!({
+            "design": {
+                "members": [{
+                    "name": "top",
+                    "kind": "Instance",
+                    "body": {
+                        "name": "top",
+                        "kind": "InstanceBody",
+                        "members": [{ "name": "u_leaf", "kind": "Instance", "body": leaf }]
+                    }
+                }]
+            }
+        });
+
+        let inventory = extract_signals_from_value(&ast, false);
+        assert!(inventory.instances["top"].is_empty());
+        let signals = &inventory.instances["top.u_leaf"];
+        assert_eq!(signals, &inventory.definitions["leaf"]);
+
+        let summary: Vec<_> = signals
+            .iter()
+            .map(|signal| {
+                (
+                    signal.name.as_str(),
+                    signal.hier_prefix.as_str(),
+                    signal.is_port,
+                    signal.unpacked_size,
+                    signal.drivers.as_slice(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("q", "", true, None, &[SignalDriver::ContinuousAssign][..]),
+                ("mem", "", false, Some(4), &[SignalDriver::AlwaysFf][..]),
+                (
+                    "ready",
+                    "",
+                    false,
+                    None,
+                    &[SignalDriver::ContinuousAssign][..]
+                ),
+                (
+                    "t",
+                    ".g",
+                    false,
+                    None,
+                    &[SignalDriver::ContinuousAssign][..]
+                ),
+            ]
+        );
+        assert_eq!(
+            signals[2].kind,
+            SignalKind::Net {
+                net_type: "wire".to_string()
+            }
+        );
+        assert_eq!(signals[0].kind, SignalKind::Variable);
+        assert_eq!(signals[1].ty.unpacked_dimensions().len(), 1);
+    }
+}
@@ -0,0 +1,199 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Extraction of ports and parameters for every distinct parameterization of
+//! every module in the elaborated design, not just the top-level ones.
+
+use crate::expression::link_name;
+use crate::extract::{ParameterDef, Port, TypeResolver, body_parameter_defs, body_ports};
+use crate::hierarchy::hierarchy_with_bodies;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::error::Error;
+
+/// One module definition elaborated with one set of parameter values.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Specialization {
+    pub def_name: String,
+    pub parameters: Vec<ParameterDef>,
+    pub ports: Vec<Port>,
+    /// The full hierarchical paths of the instances using this
+    /// specialization, in design order, e.g. `top.u_core.genblk1.u_fifo`.
+    pub instances: Vec<String>,
+}
+
+pub fn extract_specializations(
+    cfg: &crate::SlangConfig,
+    skip_unsupported: bool,
+) -> Result<HashMap<String, Specialization>, Box<dyn Error>> {
+    Ok(specializations(
+        &crate::run_slang(cfg)?,
+        skip_unsupported,
+        cfg.parameters,
+    ))
+}
+
+/// Walks the whole design and returns one entry per distinct instance body.
+///
+/// Entries are keyed by the definition name followed by its non-local
+/// parameter values, e.g. `fifo #(.W(8), .T(logic[7:0]))`, or just the
+/// definition name for a module without parameters.
+pub fn extract_specializations_from_value(
+    value: &Value,
+    skip_unsupported: bool,
+) -> HashMap<String, Specialization> {
+    specializations(value, skip_unsupported, &[])
+}
+
+fn specializations(
+    value: &Value,
+    skip_unsupported: bool,
+    overrides: &[(&str, &str)],
+) -> HashMap<String, Specialization> {
+    let type_resolver = TypeResolver::new(value);
+    let (hierarchy, bodies) = hierarchy_with_bodies(value);
+    let mut specializations: HashMap<String, Specialization> = HashMap::new();
+    for id in hierarchy.depth_first() {
+        let Some(body) = bodies[id.index()] else {
+            continue;
+        };
+        let def_name = &hierarchy[id].def_name;
+        match specializations.entry(specialization_key(def_name, body)) {
+            Entry::Occupied(mut entry) => entry.get_mut().instances.push(hierarchy.path(id)),
+            Entry::Vacant(entry) => {
+                // Like Slang's `-G`, overrides only apply to top-level modules.
+                let overrides = match hierarchy.parent(id) {
+                    Some(_) => &[],
+                    None => overrides,
+                };
+                entry.insert(Specialization {
+                    def_name: def_name.clone(),
+                    parameters: body_parameter_defs(
+                        &type_resolver,
+                        body,
+                        skip_unsupported,
+                        overrides,
+                    ),
+                    ports: body_ports(&type_resolver, body, skip_unsupported),
+                    instances: vec![hierarchy.path(id)],
+                });
+            }
+        }
+    }
+    specializations
+}
+
+/// Builds the key identifying a body from its definition name and the values
+/// of its non-local parameters, in declaration order.
+fn specialization_key(def_name: &str, body: &Value) -> String {
+    let Some(members) = body["members"].as_array() else {
+        return def_name.to_string();
+    };
+    let parameters: Vec<String> = members
+        .iter()
+        .filter(|member| member["isLocal"].as_bool() != Some(true))
+        .filter_map(|member| {
+            let value = match member["kind"].as_str()? {
+                "Parameter" => member["value"].as_str()?,
+                "TypeParameter" => link_name(&member["type"])?,
+                _ => return None,
+            };
+            Some(format!(".{}({value})", member["name"].as_str()?))
+        })
+        .collect();
+    if parameters.is_empty() {
+        def_name.to_string()
+    } else {
+        format!("{def_name} #({})", parameters.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fifo(addr: u64, width: &str) -> Value {
+        json!({
+            "kind": "InstanceBody",
+            "name": "fifo",
+            "addr": addr,
+            "members": [
+                { "kind": "Parameter", "name": "W", "type": "int", "value": width },
+                { "kind": "TypeParameter", "name": "T", "type": "logic[7:0]" },
+                {
+                    "kind": "Parameter",
+                    "name": "D",
+                    "type": "int",
+                    "value": "4",
+                    "isLocal": true
+                },
+                { "kind": "Port", "name": "din", "type": "logic", "direction": "In" }
+            ]
+        })
+    }
+
+    #[test]
+    fn groups_instances_by_parameterization() {
+        let ast = json!({
+            "design": {
+                "members": [{
+                    "kind": "Instance",
+                    "name": "top",
+                    "body": {
+                        "kind": "InstanceBody",
+                        "name": "top",
+                        "members": [
+                            { "kind": "Instance", "name": "u_narrow", "body": fifo(1, "8") },
+                            { "kind": "Instance", "name": "u_wide", "body": fifo(2, "32") },
+                            {
+                                "kind": "InstanceArray",
+                                "name": "u_arr",
+                                "range": "[1:0]",
+                                "members": [
+                                    { "kind": "Instance", "name": "", "body": "2 fifo" },
+                                    { "kind": "Instance", "name": "", "body": "2 fifo" }
+                                ]
+                            },
+                            {
+                                "kind": "GenerateBlock",
+                                "name": "",
+                                "constructIndex": 1,
+                                "isUninstantiated": false,
+                                "members": [
+                                    { "kind": "Instance", "name": "u_again", "body": "1 fifo" }
+                                ]
+                            }
+                        ]
+                    }
+                }]
+            }
+        });
+
+        let specializations = extract_specializations_from_value(&ast, false);
+        let mut keys: Vec<_> = specializations.keys().cloned().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "fifo #(.W(32), .T(logic[7:0]))",
+                "fifo #(.W(8), .T(logic[7:0]))",
+                "top"
+            ]
+        );
+
+        let narrow = &specializations["fifo #(.W(8), .T(logic[7:0]))"];
+        assert_eq!(narrow.def_name, "fifo");
+        assert_eq!(
+            narrow.instances,
+            vec!["top.u_narrow", "top.genblk1.u_again"]
+        );
+        assert_eq!(narrow.parameters.len(), 3);
+        assert_eq!(narrow.ports[0].name, "din");
+        assert_eq!(
+            specializations["fifo #(.W(32), .T(logic[7:0]))"].instances,
+            vec!["top.u_wide", "top.u_arr[0]", "top.u_arr[1]"]
+        );
+        assert_eq!(specializations["top"].instances, vec!["top"]);
+    }
+}
@@ -0,0 +1,260 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Elaboration of a design under several combinations of parameter overrides,
+//! to check how the ports of its top-level modules depend on them.
+
+use crate::extract::try_extract_ports_from_value;
+use crate::{Port, SlangConfig};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+
+/// The outcome of elaborating the design with one combination of overrides.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SweepRun {
+    /// The overrides of this run, applied after those of the base config.
+    pub parameters: Vec<(String, String)>,
+    /// The ports of each top-level module, or the error, including Slang's
+    /// diagnostics, if the design failed to elaborate.
+    pub result: Result<HashMap<String, Vec<Port>>, String>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParameterSweep {
+    /// One run per combination, in the order the combinations were given.
+    pub runs: Vec<SweepRun>,
+}
+
+/// One port of a module across the runs of a sweep.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PortSweep {
+    pub name: String,
+    /// For each run that elaborated the module, the run's index and the port,
+    /// or `None` if the module has no such port in that run.
+    pub ports: Vec<(usize, Option<Port>)>,
+}
+
+impl PortSweep {
+    /// Whether the port is present with the same direction and width in every
+    /// run that elaborated its module.
+    pub fn is_stable(&self) -> bool {
+        let mut shapes = self
+            .ports
+            .iter()
+            .map(|(_, port)| port.as_ref().map(|port| (port.dir, port.ty.width().ok())));
+        match shapes.next() {
+            Some(first) => first.is_some() && shapes.all(|shape| shape == first),
+            None => true,
+        }
+    }
+
+    /// The width of the port in each run that elaborated its module, or
+    /// `None` where the port is missing or its width is unknown.
+    pub fn widths(&self) -> Vec<(usize, Option<usize>)> {
+        self.ports
+            .iter()
+            .map(|(run, port)| (*run, port.as_ref().and_then(|port| port.ty.width().ok())))
+            .collect()
+    }
+}
+
+impl ParameterSweep {
+    /// The runs that failed to elaborate, with their overrides and errors.
+    pub fn failures(&self) -> Vec<(&[(String, String)], &str)> {
+        self.runs
+            .iter()
+            .filter_map(|run| match &run.result {
+                Ok(_) => None,
+                Err(error) => Some((run.parameters.as_slice(), error.as_str())),
+            })
+            .collect()
+    }
+
+    /// Returns how each port of each top-level module varies across the runs
+    /// that elaborated. Ports are listed in the order they first appear.
+    pub fn port_variations(&self) -> HashMap<String, Vec<PortSweep>> {
+        let mut variations: HashMap<String, Vec<PortSweep>> = HashMap::new();
+        for run in &self.runs {
+            let Ok(modules) = &run.result else {
+                continue;
+            };
+            for (module, ports) in modules {
+                let sweeps = variations.entry(module.clone()).or_default();
+                for port in ports {
+                    if !sweeps.iter().any(|sweep| sweep.name == port.name) {
+                        sweeps.push(PortSweep {
+                            name: port.name.clone(),
+                            ports: Vec::new(),
+                        });
+                    }
+                }
+            }
+        }
+
+        for (module, sweeps) in variations.iter_mut() {
+            for (index, run) in self.runs.iter().enumerate() {
+                let Some(ports) = run.result.as_ref().ok().and_then(|m| m.get(module)) else {
+                    continue;
+                };
+                for sweep in sweeps.iter_mut() {
+                    let port = ports.iter().find(|port| port.name == sweep.name);
+                    sweep.ports.push((index, port.cloned()));
+                }
+            }
+        }
+        variations
+    }
+}
+
+/// Elaborates the design once per combination of parameter overrides, added
+/// to those of `cfg`, and extracts the ports of its top-level modules.
+///
+/// Slang's output is always captured, so that the diagnostics of a failed
+/// run are part of its error. A run whose ports cannot be extracted, such as
+/// one with a port of an unsupported type while `skip_unsupported` is not
+/// set, fails on its own without stopping the sweep.
+pub fn sweep_parameters(
+    cfg: &SlangConfig,
+    combinations: &[&[(&str, &str)]],
+    skip_unsupported: bool,
+) -> ParameterSweep {
+    let runs = combinations
+        .iter()
+        .map(|combination| {
+            let parameters: Vec<(&str, &str)> = cfg
+                .parameters
+                .iter()
+                .chain(combination.iter())
+                .copied()
+                .collect();
+            let run_cfg = SlangConfig {
+                parameters: &parameters,
+                capture_stdio: true,
+                ..*cfg
+            };
+            sweep_run(combination, crate::run_slang(&run_cfg), skip_unsupported)
+        })
+        .collect();
+    ParameterSweep { runs }
+}
+
+/// Records the outcome of one run from Slang's output.
+fn sweep_run(
+    combination: &[(&str, &str)],
+    output: Result<Value, Box<dyn Error>>,
+    skip_unsupported: bool,
+) -> SweepRun {
+    SweepRun {
+        parameters: combination
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+        result: output
+            .map_err(|error| error.to_string())
+            .and_then(|value| try_extract_ports_from_value(&value, skip_unsupported)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PortDir, parse_type_definition};
+    use serde_json::json;
+
+    fn port(name: &str, ty: &str) -> Port {
+        Port {
+            dir: PortDir::Input,
+            name: name.to_string(),
+            ty: parse_type_definition(ty).unwrap(),
+            kind: None,
+            default: None,
+            expression: None,
+            location: None,
+            attributes: vec![],
+        }
+    }
+
+    fn run(width: &str, ports: Option<Vec<Port>>) -> SweepRun {
+        SweepRun {
+            parameters: vec![("W".to_string(), width.to_string())],
+            result: ports
+                .map(|ports| HashMap::from([("top".to_string(), ports)]))
+                .ok_or_else(|| "slang command failed".to_string()),
+        }
+    }
+
+    #[test]
+    fn reports_port_variations() {
+        let sweep = ParameterSweep {
+            runs: vec![
+                run(
+                    "8",
+                    Some(vec![port("clk", "logic"), port("d", "logic[7:0]")]),
+                ),
+                run("0", None),
+                run(
+                    "16",
+                    Some(vec![
+                        port("clk", "logic"),
+                        port("d", "logic[15:0]"),
+                        port("dbg", "logic"),
+                    ]),
+                ),
+            ],
+        };
+
+        let variations = sweep.port_variations();
+        let top = &variations["top"];
+        let names: Vec<&str> = top.iter().map(|sweep| sweep.name.as_str()).collect();
+        assert_eq!(names, vec!["clk", "d", "dbg"]);
+
+        assert!(top[0].is_stable());
+        assert!(!top[1].is_stable());
+        assert_eq!(top[1].widths(), vec![(0, Some(8)), (2, Some(16))]);
+        assert!(!top[2].is_stable());
+        assert_eq!(top[2].widths(), vec![(0, None), (2, Some(1))]);
+
+        let failures = sweep.failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, &[("W".to_string(), "0".to_string())]);
+        assert_eq!(failures[0].1, "slang command failed");
+    }
+
+    #[test]
+    fn records_unsupported_ports_as_failed_runs() {
+        let design = |ty: &str| {
+            json!({
+                "design": {
+                    "members": [{
+                        "name": "top",
+                        "kind": "Instance",
+                        "body": {
+                            "name": "top",
+                            "kind": "InstanceBody",
+                            "members": [{
+                                "name": "d",
+                                "kind": "Port",
+                                "type": ty,
+                                "direction": "In",
+                                "internalSymbol": "1 d"
+                            }]
+                        }
+                    }]
+                }
+            })
+        };
+
+        let supported = sweep_run(&[("T", "logic")], Ok(design("logic")), false);
+        assert_eq!(supported.result.unwrap()["top"][0].name, "d");
+
+        let unsupported = sweep_run(&[("T", "string")], Ok(design("string")), false);
+        assert_eq!(
+            unsupported.parameters,
+            vec![("T".to_string(), "string".to_string())]
+        );
+        assert!(unsupported.result.is_err());
+
+        let skipped = sweep_run(&[("T", "string")], Ok(design("string")), true);
+        assert!(skipped.result.unwrap()["top"].is_empty());
+    }
+}
@@ -43,19 +43,35 @@ mod tests {
                 Port {
                     name: "x".to_string(),
                     dir: PortDir::Input,
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     name: "y".to_string(),
                     dir: PortDir::Output,
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
             ],
@@ -73,12 +89,24 @@ mod tests {
                 def_name: "A".to_string(),
                 inst_name: "A".to_string(),
                 hier_prefix: "".to_string(),
+                kind: InstanceKind::Module,
+                connections: vec![],
+                parameters: vec![],
+                unsupported_parameters: vec![],
                 contents: vec![Rc::new(RefCell::new(Instance {
                     def_name: "B".to_string(),
                     inst_name: "b".to_string(),
                     hier_prefix: "".to_string(),
+                    kind: InstanceKind::Module,
+                    connections: vec![],
+                    parameters: vec![],
+                    unsupported_parameters: vec![],
                     contents: vec![],
+                    location: None,
+                    attributes: vec![],
                 }))],
+                location: None,
+                attributes: vec![],
             },
         )]);
 
@@ -97,8 +125,15 @@ mod tests {
                     Parameter {
                         name: "myparam".to_string(),
                         value: "42".to_string(),
+                        ty: Some(parse_type_definition("int").unwrap()),
+                        location: None,
                     },
                 )]),
+                typedefs: HashMap::new(),
+                enums: HashMap::new(),
+                subroutines: HashMap::new(),
+                imports: vec![],
+                location: None,
             },
         )]);
 
@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use slang_rs::*;
+
+    #[test]
+    fn test_generate_cpp_header() {
+        let verilog = str2tmpfile(
+            "
+            package my_pkg;
+              localparam int WIDTH = 8;
+              typedef enum logic [1:0] {
+                IDLE = 0,
+                BUSY = 1
+              } state_t;
+              typedef struct packed {
+                logic [WIDTH-1:0] data;
+                state_t state;
+              } pkt_t;
+            endpackage
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            ..Default::default()
+        };
+
+        let pkgs = extract_packages(&cfg).unwrap();
+        let header = generate_cpp_header(&pkgs["my_pkg"], false).unwrap();
+
+        assert!(header.contains("namespace my_pkg {"));
+        assert!(header.contains("constexpr int32_t WIDTH = 8;"));
+        assert!(header.contains("enum class state_t : uint8_t {\n  IDLE = 0u,\n  BUSY = 1u,\n};"));
+        assert!(header.contains("  static constexpr unsigned kWidth = 10;"));
+        assert!(header.contains(
+            "  uint8_t data() const {\n    \
+             return static_cast<uint8_t>(detail::get_bits(words, 2, 8));\n  }"
+        ));
+        assert!(header.contains(
+            "  state_t state() const {\n    \
+             return static_cast<state_t>(detail::get_bits(words, 0, 2));\n  }"
+        ));
+
+        // Generation is deterministic, so the header can be checked in.
+        assert_eq!(header, generate_cpp_header(&pkgs["my_pkg"], false).unwrap());
+    }
+}
@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use slang_rs::*;
+
+    #[test]
+    fn test_generate_dslx() {
+        let verilog = str2tmpfile(
+            "
+            package my_pkg;
+              localparam int WIDTH = 8;
+              typedef logic [WIDTH-1:0] byte_t;
+              typedef enum logic [1:0] {
+                IDLE = 0,
+                BUSY = 1
+              } state_t;
+              typedef struct packed {
+                logic [WIDTH-1:0] data;
+                state_t state;
+              } pkt_t;
+              typedef pkt_t [3:0] pkt_array_t;
+            endpackage
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            ..Default::default()
+        };
+
+        let pkgs = extract_packages(&cfg).unwrap();
+
+        assert_eq!(
+            generate_dslx(&pkgs["my_pkg"], false).unwrap(),
+            "// Generated from SystemVerilog package `my_pkg`.
+
+pub const WIDTH = s32:8;
+
+pub type byte_t = u8;
+
+pub enum state_t : u2 {
+    IDLE = 0,
+    BUSY = 1,
+}
+
+pub struct pkt_t {
+    data: u8,
+    state: state_t,
+}
+
+pub type pkt_array_t = pkt_t[4];
+"
+        );
+    }
+}
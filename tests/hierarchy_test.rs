@@ -35,25 +35,49 @@ mod tests {
             def_name: "A".to_string(),
             inst_name: "A".to_string(),
             hier_prefix: "".to_string(),
+            kind: InstanceKind::Module,
+            connections: vec![],
+            parameters: vec![],
+            unsupported_parameters: vec![],
             contents: vec![Rc::new(RefCell::new(Instance {
                 def_name: "B".to_string(),
                 inst_name: "b0".to_string(),
                 hier_prefix: "".to_string(),
+                kind: InstanceKind::Module,
+                connections: vec![],
+                parameters: vec![],
+                unsupported_parameters: vec![],
                 contents: vec![
                     Rc::new(RefCell::new(Instance {
                         def_name: "C".to_string(),
                         inst_name: "c0".to_string(),
                         hier_prefix: "".to_string(),
+                        kind: InstanceKind::Module,
+                        connections: vec![],
+                        parameters: vec![],
+                        unsupported_parameters: vec![],
                         contents: vec![],
+                        location: None,
+                        attributes: vec![],
                     })),
                     Rc::new(RefCell::new(Instance {
                         def_name: "C".to_string(),
                         inst_name: "c1".to_string(),
                         hier_prefix: "".to_string(),
+                        kind: InstanceKind::Module,
+                        connections: vec![],
+                        parameters: vec![],
+                        unsupported_parameters: vec![],
                         contents: vec![],
+                        location: None,
+                        attributes: vec![],
                     })),
                 ],
+                location: None,
+                attributes: vec![],
             }))],
+            location: None,
+            attributes: vec![],
         };
 
         let expected = HashMap::from([("A".to_string(), expected)]);
@@ -120,38 +144,84 @@ endmodule
             def_name: "top".to_string(),
             inst_name: "top".to_string(),
             hier_prefix: "".to_string(),
+            kind: InstanceKind::Module,
+            connections: vec![],
+            parameters: vec![ParameterDef {
+                name: "genblk2".to_string(),
+                ty: Some(parse_type_definition("int").unwrap()),
+                value: Some("0".to_string()),
+                kind: ParameterKind::Value,
+                is_local: false,
+                is_port: false,
+                source: ParameterSource::Default,
+                location: None,
+                attributes: vec![],
+            }],
+            unsupported_parameters: vec![],
             contents: vec![
                 Rc::new(RefCell::new(Instance {
                     def_name: "B".to_string(),
                     inst_name: "b".to_string(),
                     hier_prefix: ".genblk1".to_string(),
+                    kind: InstanceKind::Module,
+                    connections: vec![],
+                    parameters: vec![],
+                    unsupported_parameters: vec![],
                     contents: vec![],
+                    location: None,
+                    attributes: vec![],
                 })),
                 Rc::new(RefCell::new(Instance {
                     def_name: "B".to_string(),
                     inst_name: "b".to_string(),
                     hier_prefix: ".genblk02".to_string(),
+                    kind: InstanceKind::Module,
+                    connections: vec![],
+                    parameters: vec![],
+                    unsupported_parameters: vec![],
                     contents: vec![],
+                    location: None,
+                    attributes: vec![],
                 })),
                 Rc::new(RefCell::new(Instance {
                     def_name: "A".to_string(),
                     inst_name: "a".to_string(),
                     hier_prefix: ".g1[0].genblk1".to_string(),
+                    kind: InstanceKind::Module,
+                    connections: vec![],
+                    parameters: vec![],
+                    unsupported_parameters: vec![],
                     contents: vec![],
+                    location: None,
+                    attributes: vec![],
                 })),
                 Rc::new(RefCell::new(Instance {
                     def_name: "A".to_string(),
                     inst_name: "a".to_string(),
                     hier_prefix: ".genblk4[0].genblk1".to_string(),
+                    kind: InstanceKind::Module,
+                    connections: vec![],
+                    parameters: vec![],
+                    unsupported_parameters: vec![],
                     contents: vec![],
+                    location: None,
+                    attributes: vec![],
                 })),
                 Rc::new(RefCell::new(Instance {
                     def_name: "A".to_string(),
                     inst_name: "a".to_string(),
                     hier_prefix: ".genblk5".to_string(),
+                    kind: InstanceKind::Module,
+                    connections: vec![],
+                    parameters: vec![],
+                    unsupported_parameters: vec![],
                     contents: vec![],
+                    location: None,
+                    attributes: vec![],
                 })),
             ],
+            location: None,
+            attributes: vec![],
         };
 
         let expected = HashMap::from([("top".to_string(), expected)]);
@@ -213,29 +283,372 @@ endmodule
                 def_name: "A".to_string(),
                 inst_name: "A".to_string(),
                 hier_prefix: "".to_string(),
+                kind: InstanceKind::Module,
+                connections: vec![],
+                parameters: vec![],
+                unsupported_parameters: vec![],
                 contents: vec![
                     Rc::new(RefCell::new(Instance {
                         def_name: "B".to_string(),
                         inst_name: "b".to_string(),
                         hier_prefix: "".to_string(),
+                        kind: InstanceKind::Uninstantiated,
+                        connections: vec![],
+                        parameters: vec![],
+                        unsupported_parameters: vec![],
                         contents: vec![],
+                        location: None,
+                        attributes: vec![],
                     })),
                     Rc::new(RefCell::new(Instance {
                         def_name: "C".to_string(),
                         inst_name: "c".to_string(),
                         hier_prefix: ".genblk1".to_string(),
+                        kind: InstanceKind::Uninstantiated,
+                        connections: vec![],
+                        parameters: vec![],
+                        unsupported_parameters: vec![],
                         contents: vec![],
+                        location: None,
+                        attributes: vec![],
                     })),
                     Rc::new(RefCell::new(Instance {
                         def_name: "E".to_string(),
                         inst_name: "e".to_string(),
                         hier_prefix: "".to_string(),
+                        kind: InstanceKind::Module,
+                        connections: vec![],
+                        parameters: vec![],
+                        unsupported_parameters: vec![],
                         contents: vec![],
+                        location: None,
+                        attributes: vec![],
                     })),
                 ],
+                location: None,
+                attributes: vec![],
             },
         )]);
 
         assert_eq!(hierarchy, expected);
     }
+
+    #[test]
+    fn test_extract_hierarchy_tree() {
+        let verilog = str2tmpfile(
+            "
+            module leaf;
+            endmodule
+            module mid;
+              for (genvar i = 0; i < 2; i++) begin : g
+                leaf u_leaf();
+              end
+            endmodule
+            module top;
+              mid u_mid();
+            endmodule
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            ..Default::default()
+        };
+
+        let hierarchy = extract_hierarchy_tree(&cfg).unwrap();
+        let leaf = hierarchy.find("top.u_mid.g[1].u_leaf").unwrap();
+        assert_eq!(hierarchy[leaf].def_name, "leaf");
+        assert_eq!(hierarchy.path(hierarchy.parent(leaf).unwrap()), "top.u_mid");
+
+        let leaves: Vec<String> = hierarchy
+            .glob("top.*.g[*].u_leaf")
+            .into_iter()
+            .map(|id| hierarchy.path(id))
+            .collect();
+        assert_eq!(
+            leaves,
+            vec!["top.u_mid.g[0].u_leaf", "top.u_mid.g[1].u_leaf"]
+        );
+    }
+
+    #[test]
+    fn test_port_connections() {
+        let verilog = str2tmpfile(
+            "
+            module leaf(
+              input logic clk,
+              input logic [3:0] d,
+              output logic [3:0] q,
+              input logic en
+            );
+            endmodule
+            module top;
+              logic clk;
+              logic [7:0] a;
+              logic [3:0] q, en;
+              leaf u_named(.clk, .d(a[3:0]), .q(), .en(en[0]));
+              leaf u_positional(clk, a[7:4], q, );
+              leaf u_wildcard(.d(a[3:0]), .*);
+            endmodule
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            tops: &["top"],
+            include_source_info: true,
+            ..Default::default()
+        };
+
+        let mut hierarchy = extract_hierarchy_tree(&cfg).unwrap();
+        let unread = &hierarchy[hierarchy.find("top.u_named").unwrap()].connections;
+        assert!(unread.iter().all(|c| c.style.is_none()));
+        hierarchy.read_connection_styles();
+
+        let named = &hierarchy[hierarchy.find("top.u_named").unwrap()].connections;
+        assert_eq!(
+            named[0],
+            PortConnection {
+                port: "clk".to_string(),
+                dir: Some(PortDir::Input),
+                expression: Some("clk".to_string()),
+                target: Some("clk".to_string()),
+                style: Some(ConnectionStyle::ImplicitNamed),
+            }
+        );
+        assert_eq!(named[1].expression.as_deref(), Some("a[3:0]"));
+        assert_eq!(named[1].target, None);
+        assert_eq!(named[1].style, Some(ConnectionStyle::Named));
+        assert_eq!(named[2].dir, Some(PortDir::Output));
+        assert!(!named[2].is_connected());
+
+        let positional = &hierarchy[hierarchy.find("top.u_positional").unwrap()].connections;
+        assert_eq!(positional[2].target.as_deref(), Some("q"));
+        assert!(!positional[3].is_connected());
+        assert!(
+            positional
+                .iter()
+                .all(|c| c.style == Some(ConnectionStyle::Positional))
+        );
+
+        let wildcard = &hierarchy[hierarchy.find("top.u_wildcard").unwrap()].connections;
+        assert_eq!(wildcard[0].style, Some(ConnectionStyle::Wildcard));
+        assert_eq!(wildcard[1].style, Some(ConnectionStyle::Named));
+        assert_eq!(wildcard[3].target.as_deref(), Some("en"));
+    }
+
+    #[test]
+    fn test_instance_parameters() {
+        let verilog = str2tmpfile(
+            "
+            module mem #(
+                parameter int DEPTH = 16,
+                parameter type T = logic [7:0]
+            );
+            endmodule
+            module top;
+                mem u_small();
+                mem #(.DEPTH(16)) u_small_again();
+                mem #(.DEPTH(1024)) u_large();
+                mem #(.DEPTH(1024), .T(logic [31:0])) u_wide();
+            endmodule
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            tops: &["top"],
+            ..Default::default()
+        };
+
+        let hierarchy = extract_hierarchy_tree(&cfg).unwrap();
+        let large = &hierarchy[hierarchy.find("top.u_large").unwrap()];
+        assert_eq!(large.parameters[0].value.as_deref(), Some("1024"));
+        assert_eq!(large.parameters[0].source, ParameterSource::Instance);
+        let wide = &hierarchy[hierarchy.find("top.u_wide").unwrap()];
+        assert_eq!(wide.parameters[1].ty.as_ref().unwrap().width().unwrap(), 32);
+
+        let groups: Vec<Vec<String>> = hierarchy
+            .group_by_parameters()
+            .iter()
+            .filter(|group| group.def_name == "mem")
+            .map(|group| {
+                group
+                    .instances
+                    .iter()
+                    .map(|&id| hierarchy.path(id))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                vec!["top.u_small", "top.u_small_again"],
+                vec!["top.u_large"],
+                vec!["top.u_wide"],
+            ]
+        );
+
+        let instances = extract_hierarchy(&cfg).unwrap();
+        let small = instances["top"].contents[0].borrow();
+        assert_eq!(small.parameters[0].value.as_deref(), Some("16"));
+    }
+
+    #[test]
+    fn test_instance_kinds() {
+        let verilog = str2tmpfile(
+            "
+            interface bus_if;
+              logic valid;
+            endinterface
+            module mon(bus_if bus);
+            endmodule
+            interface wrapped_if;
+              bus_if u_inner();
+              mon u_mon(u_inner);
+            endinterface
+            program test;
+            endprogram
+            module top;
+              logic a, b, y;
+              wrapped_if u_bus();
+              test u_test();
+              and g1(y, a, b);
+              missing u_missing();
+            endmodule
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            tops: &["top"],
+            ignore_unknown_modules: true,
+            ..Default::default()
+        };
+
+        let hierarchy = extract_hierarchy_tree(&cfg).unwrap();
+        let kind = |path: &str| hierarchy[hierarchy.find(path).unwrap()].kind;
+        assert_eq!(kind("top"), InstanceKind::Module);
+        assert_eq!(kind("top.u_bus"), InstanceKind::Interface);
+        assert_eq!(kind("top.u_bus.u_inner"), InstanceKind::Interface);
+        assert_eq!(kind("top.u_bus.u_mon"), InstanceKind::Module);
+        assert_eq!(kind("top.u_test"), InstanceKind::Program);
+        assert_eq!(kind("top.g1"), InstanceKind::Primitive);
+        assert_eq!(kind("top.u_missing"), InstanceKind::Uninstantiated);
+    }
+
+    #[test]
+    fn test_instance_arrays() {
+        let verilog = str2tmpfile(
+            "
+            interface bus_if;
+            endinterface
+            module leaf;
+            endmodule
+            module top;
+              bus_if u_bus[2]();
+              leaf u[3:0]();
+            endmodule
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            tops: &["top"],
+            ..Default::default()
+        };
+
+        let hierarchy = extract_hierarchy_tree(&cfg).unwrap();
+        let paths: Vec<String> = hierarchy
+            .depth_first()
+            .map(|id| hierarchy.path(id))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "top",
+                "top.u_bus[0]",
+                "top.u_bus[1]",
+                "top.u[0]",
+                "top.u[1]",
+                "top.u[2]",
+                "top.u[3]",
+            ]
+        );
+        let kind = |path: &str| hierarchy[hierarchy.find(path).unwrap()].kind;
+        assert_eq!(kind("top.u_bus[1]"), InstanceKind::Interface);
+        assert_eq!(kind("top.u[3]"), InstanceKind::Module);
+    }
+
+    #[test]
+    fn test_generate_blocks() {
+        let verilog = str2tmpfile(
+            "
+            module leaf;
+            endmodule
+            module top #(parameter int MODE = 1);
+              if (MODE == 0) begin : g_zero
+                leaf u_leaf();
+              end else begin
+                leaf u_leaf();
+              end
+              for (genvar i = 2; i < 4; i++) begin : g_loop
+                leaf u_leaf();
+              end
+            endmodule
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            tops: &["top"],
+            ..Default::default()
+        };
+
+        let hierarchy = extract_hierarchy_tree(&cfg).unwrap();
+        let blocks: Vec<(String, &GenerateBlock)> = hierarchy
+            .generate_blocks()
+            .map(|id| (hierarchy.generate_block_path(id), &hierarchy[id]))
+            .collect();
+        let paths: Vec<&str> = blocks.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "top.g_zero",
+                "top.genblk1",
+                "top.g_loop[2]",
+                "top.g_loop[3]"
+            ]
+        );
+
+        assert!(blocks[0].1.uninstantiated);
+        assert!(!blocks[0].1.auto_named);
+        assert!(!blocks[1].1.uninstantiated);
+        assert!(blocks[1].1.auto_named);
+        assert_eq!(
+            blocks[1].1.construct,
+            GenerateConstruct::Conditional { branch: 1 }
+        );
+        assert_eq!(
+            blocks[3].1.construct,
+            GenerateConstruct::Loop {
+                variable: Some("i".to_string()),
+                value: 3
+            }
+        );
+
+        let leaf = hierarchy.find("top.g_loop[3].u_leaf").unwrap();
+        assert_eq!(
+            hierarchy.generate_block(leaf),
+            hierarchy.generate_blocks().nth(3)
+        );
+        assert!(hierarchy.find("top.genblk1.u_leaf").is_some());
+        assert!(hierarchy.find("top.g_zero.u_leaf").is_none());
+    }
 }
@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use slang_rs::*;
+
+    #[test]
+    fn test_generate_ipxact() {
+        let verilog = str2tmpfile(
+            "
+            package my_pkg;
+              typedef struct packed {
+                logic [7:0] data;
+                logic valid;
+              } pkt_t;
+            endpackage
+            module my_module #(
+              parameter int WIDTH = 8
+            ) (
+              input logic clk,
+              input logic [WIDTH-1:0] a,
+              output my_pkg::pkt_t pkt
+            );
+            endmodule
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            ..Default::default()
+        };
+
+        let ports = extract_ports(&cfg, false);
+        let parameters = extract_parameter_defs(&cfg, false);
+        let options = IpXactOptions {
+            flatten_structs: true,
+            ..Default::default()
+        };
+        let xml = generate_ipxact(
+            "my_module",
+            &ports["my_module"],
+            &parameters["my_module"],
+            &cfg,
+            &options,
+        )
+        .unwrap();
+
+        assert!(xml.contains("<ipxact:moduleName>my_module</ipxact:moduleName>"));
+        assert!(xml.contains("<ipxact:value>8</ipxact:value>"));
+        assert!(xml.contains("<ipxact:name>a</ipxact:name>"));
+        assert!(xml.contains("<ipxact:name>pkt_data</ipxact:name>"));
+        assert!(xml.contains("<ipxact:name>pkt_valid</ipxact:name>"));
+        assert!(xml.contains(verilog.path().to_str().unwrap()));
+    }
+}
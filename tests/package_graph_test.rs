@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use slang_rs::*;
+
+    #[test]
+    fn test_compile_order() {
+        let top = str2tmpfile(
+            "
+            package top_pkg;
+              import base_pkg::*;
+              localparam int N = base_pkg::W * 2;
+            endpackage
+            module top;
+              logic [top_pkg::N-1:0] data;
+            endmodule
+            ",
+        )
+        .unwrap();
+        let base = str2tmpfile(
+            "
+            package base_pkg;
+              localparam int W = 4;
+            endpackage
+            ",
+        )
+        .unwrap();
+        let top_path = top.path().to_str().unwrap();
+        let base_path = base.path().to_str().unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[top_path, base_path],
+            ..Default::default()
+        };
+
+        let graph = extract_package_graph(&cfg).unwrap();
+        assert_eq!(graph.package_order().unwrap(), vec!["base_pkg", "top_pkg"]);
+
+        assert_eq!(compile_order(&cfg).unwrap(), vec![base_path, top_path]);
+    }
+}
@@ -39,8 +39,15 @@ mod tests {
                         Parameter {
                             name: "a".to_string(),
                             value: "22".to_string(),
+                            ty: Some(parse_type_definition("int").unwrap()),
+                            location: None,
                         },
                     )]),
+                    typedefs: HashMap::new(),
+                    enums: HashMap::new(),
+                    subroutines: HashMap::new(),
+                    imports: vec![],
+                    location: None,
                 },
             ),
             (
@@ -53,6 +60,8 @@ mod tests {
                             Parameter {
                                 name: "b".to_string(),
                                 value: "123".to_string(),
+                                ty: Some(parse_type_definition("int").unwrap()),
+                                location: None,
                             },
                         ),
                         (
@@ -60,16 +69,19 @@ mod tests {
                             Parameter {
                                 name: "c".to_string(),
                                 value: "145".to_string(),
-                            },
-                        ),
-                        (
-                            "my_t".to_string(),
-                            Parameter {
-                                name: "my_t".to_string(),
-                                value: "logic[33:22]".to_string(),
+                                ty: Some(parse_type_definition("int").unwrap()),
+                                location: None,
                             },
                         ),
                     ]),
+                    typedefs: HashMap::from([(
+                        "my_t".to_string(),
+                        parse_type_definition("logic[33:22]").unwrap(),
+                    )]),
+                    enums: HashMap::new(),
+                    subroutines: HashMap::new(),
+                    imports: vec![],
+                    location: None,
                 },
             ),
         ]);
@@ -131,28 +143,90 @@ mod tests {
 
         let pkgs = extract_packages(&cfg).unwrap();
 
+        assert_eq!(pkgs["my_pkg"].typedefs["my_struct_t"].width().unwrap(), 6);
+
+        assert_eq!(pkgs["my_pkg"].typedefs["my_enum_t"].width().unwrap(), 2);
+
+        assert_eq!(pkgs["my_pkg"].typedefs["my_array_t"].width().unwrap(), 24);
+    }
+
+    #[test]
+    fn test_extract_package_contents() {
+        let verilog = str2tmpfile(
+            "
+            package base_pkg;
+              localparam int W = 4;
+            endpackage
+            package my_pkg;
+              import base_pkg::*;
+              import base_pkg::W;
+              typedef enum logic [1:0] {
+                Idle = 0,
+                Busy = 2
+              } state_t;
+              localparam state_t Reset = Busy;
+              function automatic logic [3:0] add(input logic [3:0] a, input logic [3:0] b);
+                return a + b;
+              endfunction
+              task automatic tick(output int count);
+                count = 1;
+              endtask
+            endpackage
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            ..Default::default()
+        };
+
+        let pkgs = extract_packages(&cfg).unwrap();
+        let pkg = &pkgs["my_pkg"];
+
         assert_eq!(
-            parse_type_definition(&pkgs["my_pkg"]["my_struct_t"].value)
-                .unwrap()
-                .width()
-                .unwrap(),
-            6
+            pkg.imports,
+            vec![
+                Import {
+                    package: "base_pkg".to_string(),
+                    item: None,
+                },
+                Import {
+                    package: "base_pkg".to_string(),
+                    item: Some("W".to_string()),
+                },
+            ]
         );
 
+        let names: Vec<&str> = pkg.enums["state_t"]
+            .iter()
+            .map(|variant| variant.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Idle", "Busy"]);
+        assert_eq!(pkg.typedefs["state_t"].width().unwrap(), 2);
+        assert_eq!(pkg["Reset"].ty.as_ref().unwrap().width().unwrap(), 2);
         assert_eq!(
-            parse_type_definition(&pkgs["my_pkg"]["my_enum_t"].value)
-                .unwrap()
-                .width()
-                .unwrap(),
-            2
+            pkg["Reset"].const_value().unwrap(),
+            ConstValue::integer(2, false, 2)
         );
 
+        let add = &pkg.subroutines["add"];
+        assert_eq!(add.kind, SubroutineKind::Function);
+        assert_eq!(add.return_type.as_ref().unwrap().width().unwrap(), 4);
+        let arguments: Vec<(&str, &PortDir)> = add
+            .arguments
+            .iter()
+            .map(|argument| (argument.name.as_str(), &argument.dir))
+            .collect();
         assert_eq!(
-            parse_type_definition(&pkgs["my_pkg"]["my_array_t"].value)
-                .unwrap()
-                .width()
-                .unwrap(),
-            24
+            arguments,
+            vec![("a", &PortDir::Input), ("b", &PortDir::Input)]
         );
+
+        let tick = &pkg.subroutines["tick"];
+        assert_eq!(tick.kind, SubroutineKind::Task);
+        assert_eq!(tick.return_type, None);
+        assert_eq!(tick.arguments[0].dir, PortDir::Output);
+        assert_eq!(tick.arguments[0].ty.as_ref().unwrap().width().unwrap(), 32);
     }
 }
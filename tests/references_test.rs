@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use slang_rs::*;
+
+    #[test]
+    fn test_extract_hierarchical_references() {
+        let verilog = str2tmpfile(
+            "
+            module dut(input logic clk);
+                logic [3:0] state;
+                always_ff @(posedge clk) state <= state + top.step;
+            endmodule
+            module monitor;
+                logic seen;
+                assign seen = top.u_dut.state[0];
+            endmodule
+            module top;
+                logic clk;
+                logic [3:0] step;
+                dut u_dut(.clk);
+                monitor u_mon();
+                monitor u_mons[1:0]();
+                assign step = u_dut.state;
+            endmodule
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            tops: &["top"],
+            ..Default::default()
+        };
+
+        let references = extract_hierarchical_references(&cfg).unwrap();
+        let find = |source: &str, target: &str| {
+            references
+                .iter()
+                .find(|reference| {
+                    reference.source_scope == source && reference.target_path == target
+                })
+                .unwrap_or_else(|| panic!("no reference from {source} to {target}"))
+        };
+
+        let up = find("top.u_dut", "top.step");
+        assert_eq!(up.direction, Some(ReferenceDirection::Upward));
+        assert_eq!(up.target_kind.as_deref(), Some("Variable"));
+
+        let across = find("top.u_mon", "top.u_dut.state");
+        assert_eq!(across.direction, Some(ReferenceDirection::Sideways));
+        assert!(across.crosses_instances());
+
+        let down = find("top", "top.u_dut.state");
+        assert_eq!(down.direction, Some(ReferenceDirection::Downward));
+
+        let arrayed = find("top.u_mons[1]", "top.u_dut.state");
+        assert_eq!(arrayed.direction, Some(ReferenceDirection::Sideways));
+
+        assert_eq!(references.len(), 5);
+    }
+}
@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use slang_rs::*;
+
+    #[test]
+    fn test_extract_signals() {
+        let verilog = str2tmpfile(
+            "
+            module counter #(parameter int W = 4) (
+                input logic clk,
+                output logic [W-1:0] count
+            );
+                logic [W-1:0] next;
+                logic [7:0] history [4];
+                wire done = &count;
+
+                always_comb next = count + 1;
+                always_ff @(posedge clk) begin
+                    count <= next;
+                    history[0] <= 8'(count);
+                end
+            endmodule
+            module top(input logic clk);
+                counter #(.W(8)) u_wide(.clk, .count());
+                counter u_narrow(.clk, .count());
+                counter u_lanes[2](.clk, .count());
+            endmodule
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            tops: &["top"],
+            ..Default::default()
+        };
+
+        let inventory = extract_signals(&cfg, false).unwrap();
+        let find = |path: &str, name: &str| {
+            inventory.instances[path]
+                .iter()
+                .find(|signal| signal.name == name)
+                .unwrap()
+                .clone()
+        };
+
+        let count = find("top.u_wide", "count");
+        assert!(count.is_port);
+        assert_eq!(count.kind, SignalKind::Variable);
+        assert_eq!(count.ty.width().unwrap(), 8);
+        assert_eq!(count.drivers, vec![SignalDriver::AlwaysFf]);
+        assert_eq!(find("top.u_narrow", "count").ty.width().unwrap(), 4);
+        assert_eq!(
+            find("top.u_lanes[1]", "count").drivers,
+            vec![SignalDriver::AlwaysFf]
+        );
+
+        let next = find("top.u_wide", "next");
+        assert!(!next.is_port);
+        assert_eq!(next.drivers, vec![SignalDriver::AlwaysComb]);
+
+        let history = find("top.u_wide", "history");
+        assert_eq!(history.unpacked_size, Some(4));
+        assert_eq!(history.drivers, vec![SignalDriver::AlwaysFf]);
+
+        let done = find("top.u_wide", "done");
+        assert_eq!(
+            done.kind,
+            SignalKind::Net {
+                net_type: "wire".to_string()
+            }
+        );
+        assert_eq!(done.drivers, vec![SignalDriver::ContinuousAssign]);
+
+        assert_eq!(
+            inventory.definitions["counter"],
+            inventory.instances["top.u_wide"]
+        );
+        assert!(find("top", "clk").drivers.is_empty());
+    }
+}
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use slang_rs::*;
+
+    #[test]
+    fn test_extract_specializations() {
+        let verilog = str2tmpfile(
+            "
+            module fifo #(
+                parameter int W = 8
+            ) (
+                input logic [W-1:0] din
+            );
+            endmodule
+            module top;
+                fifo #(.W(8)) u_a();
+                fifo #(.W(32)) u_b();
+                fifo #(.W(16)) u_arr[1:0]();
+                for (genvar i = 0; i < 2; i++) begin : g
+                    fifo u_c();
+                end
+            endmodule
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            tops: &["top"],
+            ..Default::default()
+        };
+
+        let specializations = extract_specializations(&cfg, false).unwrap();
+        assert_eq!(specializations.len(), 4);
+
+        let narrow = &specializations["fifo #(.W(8))"];
+        assert_eq!(
+            narrow.instances,
+            vec!["top.u_a", "top.g[0].u_c", "top.g[1].u_c"]
+        );
+        assert_eq!(narrow.ports[0].ty.width().unwrap(), 8);
+
+        let wide = &specializations["fifo #(.W(32))"];
+        assert_eq!(wide.instances, vec!["top.u_b"]);
+        assert_eq!(wide.ports[0].ty.width().unwrap(), 32);
+        assert_eq!(wide.parameters[0].value.as_deref(), Some("32"));
+        assert_eq!(wide.parameters[0].source, ParameterSource::Instance);
+        assert_eq!(
+            specializations["fifo #(.W(8))"].parameters[0].source,
+            ParameterSource::Instance
+        );
+
+        assert_eq!(
+            specializations["fifo #(.W(16))"].instances,
+            vec!["top.u_arr[0]", "top.u_arr[1]"]
+        );
+
+        assert_eq!(specializations["top"].instances, vec!["top"]);
+    }
+}
@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use slang_rs::*;
+
+    #[test]
+    fn test_sweep_parameters() {
+        let verilog = str2tmpfile(
+            "
+            module top #(
+                parameter int W = 8,
+                parameter bit DEBUG = 0
+            ) (
+                input logic clk,
+                input logic [W-1:0] d,
+                output logic [$clog2(W)-1:0] q
+            );
+                if (W < 2) begin : g_check
+                    $error(\"W must be at least 2\");
+                end
+            endmodule
+            ",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            tops: &["top"],
+            ..Default::default()
+        };
+
+        let sweep = sweep_parameters(
+            &cfg,
+            &[&[("W", "8")], &[("W", "16"), ("DEBUG", "1")], &[("W", "1")]],
+            false,
+        );
+
+        let failures = sweep.failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, &[("W".to_string(), "1".to_string())]);
+        assert!(failures[0].1.contains("W must be at least 2"));
+
+        let variations = sweep.port_variations();
+        let top = &variations["top"];
+        assert!(top[0].is_stable());
+        assert_eq!(top[1].name, "d");
+        assert_eq!(top[1].widths(), vec![(0, Some(8)), (1, Some(16))]);
+        assert_eq!(top[2].widths(), vec![(0, Some(3)), (1, Some(4))]);
+    }
+}
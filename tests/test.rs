@@ -50,15 +50,30 @@ mod tests {
                 Port {
                     dir: PortDir::Input,
                     name: "a".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Output,
                     name: "b".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![
@@ -69,111 +84,251 @@ mod tests {
                             Range { msb: 0, lsb: 111 },
                             Range { msb: 1111, lsb: 0 }
                         ],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Output,
                     name: "c".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![Range { msb: 2, lsb: 0 }],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Input,
                     name: "d".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![Range { msb: 3, lsb: 0 }],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Output,
                     name: "e".to_string(),
+                    kind: Some(PortKind::Variable),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![Range { msb: 4, lsb: 0 }],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Output,
                     name: "f".to_string(),
+                    kind: Some(PortKind::Variable),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![Range { msb: 5, lsb: 0 }],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Output,
                     name: "g".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![Range { msb: 6, lsb: 0 }],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Input,
                     name: "h".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![Range { msb: 7, lsb: 0 }],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Output,
                     name: "i".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: true,
                         packed_dimensions: vec![Range { msb: 8, lsb: 0 }],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Input,
                     name: "j".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![Range { msb: 9, lsb: 0 }],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Output,
                     name: "k".to_string(),
+                    kind: Some(PortKind::Variable),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![Range { msb: 10, lsb: 0 }],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::InOut,
                     name: "l".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![Range { msb: 0, lsb: 11 }],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Output,
                     name: "m".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![Range { msb: 41, lsb: 0 }],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
             ]
         );
     }
 
+    #[test]
+    fn test_extract_port_kinds_defaults_and_expressions() {
+        let verilog = str2tmpfile(
+            "
+        module ansi (
+            ref logic [7:0] r,
+            input var logic v,
+            input logic en = 1'b1,
+            input tri t
+        );
+        endmodule
+        module explicit (.a({b, c}), d);
+            input logic b;
+            input logic [1:0] c;
+            input logic d;
+        endmodule",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            ..Default::default()
+        };
+
+        let definitions = extract_ports(&cfg, false);
+        let ansi = &definitions["ansi"];
+        assert_eq!(ansi[0].dir, PortDir::Ref);
+        assert_eq!(ansi[0].kind, Some(PortKind::Variable));
+        assert_eq!(ansi[0].ty.width().unwrap(), 8);
+        assert_eq!(ansi[1].dir, PortDir::Input);
+        assert_eq!(ansi[1].kind, Some(PortKind::Variable));
+        assert_eq!(
+            ansi[2].kind,
+            Some(PortKind::Net {
+                net_type: "wire".to_string(),
+            })
+        );
+        assert_eq!(ansi[2].default.as_deref(), Some("1'b1"));
+        assert_eq!(ansi[2].expression, None);
+        assert_eq!(
+            ansi[3].kind,
+            Some(PortKind::Net {
+                net_type: "tri".to_string(),
+            })
+        );
+        assert_eq!(ansi[3].default, None);
+
+        let explicit = &definitions["explicit"];
+        assert_eq!(explicit[0].name, "a");
+        assert_eq!(explicit[0].dir, PortDir::Input);
+        assert_eq!(explicit[0].kind, None);
+        assert_eq!(explicit[0].expression.as_deref(), Some("{b, c}"));
+        assert_eq!(explicit[0].ty.width().unwrap(), 3);
+        assert_eq!(explicit[1].name, "d");
+        assert_eq!(explicit[1].expression, None);
+    }
+
     #[test]
     fn test_union() {
         let verilog = str2tmpfile(
@@ -203,17 +358,33 @@ mod tests {
                 Port {
                     dir: PortDir::Input,
                     name: "clk".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Input,
                     name: "bus".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Union {
                         name: "bus_t".to_string(),
+                        packed: false,
                         fields: vec![
                             Field {
                                 name: "data".to_string(),
@@ -221,6 +392,7 @@ mod tests {
                                     signed: false,
                                     packed_dimensions: vec![Range { msb: 7, lsb: 0 }],
                                     unpacked_dimensions: vec![],
+                                    aliases: vec![],
                                 },
                             },
                             Field {
@@ -229,11 +401,13 @@ mod tests {
                                     signed: false,
                                     packed_dimensions: vec![],
                                     unpacked_dimensions: vec![],
+                                    aliases: vec![],
                                 },
                             },
                         ],
                         packed_dimensions: vec![],
                         unpacked_dimensions: vec![],
+                        aliases: vec!["bus_t".to_string()],
                     },
                 }
             ]
@@ -271,17 +445,31 @@ mod tests {
                 Port {
                     dir: PortDir::Input,
                     name: "clk".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Output,
                     name: "bus".to_string(),
+                    kind: Some(PortKind::Variable),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Struct {
                         name: "bus_t".to_string(),
+                        packed: false,
                         fields: vec![
                             Field {
                                 name: "data".to_string(),
@@ -289,6 +477,7 @@ mod tests {
                                     signed: false,
                                     packed_dimensions: vec![Range { msb: 7, lsb: 0 }],
                                     unpacked_dimensions: vec![],
+                                    aliases: vec![],
                                 },
                             },
                             Field {
@@ -297,11 +486,13 @@ mod tests {
                                     signed: false,
                                     packed_dimensions: vec![],
                                     unpacked_dimensions: vec![],
+                                    aliases: vec![],
                                 },
                             },
                         ],
                         packed_dimensions: vec![],
                         unpacked_dimensions: vec![],
+                        aliases: vec!["bus_t".to_string()],
                     },
                 },
             ]
@@ -337,18 +528,26 @@ mod tests {
             vec![Port {
                 dir: PortDir::Output,
                 name: "bus".to_string(),
+                kind: Some(PortKind::Variable),
+                default: None,
+                expression: None,
+                location: None,
+                attributes: vec![],
                 ty: Type::Struct {
                     name: "bus_t".to_string(),
+                    packed: true,
                     fields: vec![Field {
                         name: "data".to_string(),
                         ty: Type::Logic {
                             signed: false,
                             packed_dimensions: vec![Range { msb: 7, lsb: 0 }],
                             unpacked_dimensions: vec![],
+                            aliases: vec![],
                         },
                     },],
                     packed_dimensions: vec![Range { msb: 3, lsb: 0 }],
                     unpacked_dimensions: vec![],
+                    aliases: vec![],
                 },
             },]
         );
@@ -383,27 +582,36 @@ mod tests {
             vec![Port {
                 dir: PortDir::Output,
                 name: "color".to_string(),
+                kind: Some(PortKind::Variable),
+                default: None,
+                expression: None,
+                location: None,
+                attributes: vec![],
                 ty: Type::Enum {
                     name: "color_t".to_string(),
                     variants: vec![
                         Variant {
                             name: "RED".to_string(),
                             width: 2,
+                            signed: false,
                             value: BigInt::from(0),
                         },
                         Variant {
                             name: "GREEN".to_string(),
                             width: 2,
+                            signed: false,
                             value: BigInt::from(1),
                         },
                         Variant {
                             name: "BLUE".to_string(),
                             width: 2,
+                            signed: false,
                             value: BigInt::from(2),
                         },
                     ],
                     packed_dimensions: vec![Range { msb: 3, lsb: 0 }],
                     unpacked_dimensions: vec![],
+                    aliases: vec![],
                 },
             },]
         );
@@ -445,17 +653,31 @@ mod tests {
                 Port {
                     dir: PortDir::Input,
                     name: "clk".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Output,
                     name: "bus".to_string(),
+                    kind: Some(PortKind::Variable),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Struct {
                         name: "mypack::bus_t".to_string(),
+                        packed: false,
                         fields: vec![
                             Field {
                                 name: "data".to_string(),
@@ -463,6 +685,7 @@ mod tests {
                                     signed: false,
                                     packed_dimensions: vec![Range { msb: 7, lsb: 0 }],
                                     unpacked_dimensions: vec![],
+                                    aliases: vec![],
                                 },
                             },
                             Field {
@@ -471,32 +694,42 @@ mod tests {
                                     signed: false,
                                     packed_dimensions: vec![],
                                     unpacked_dimensions: vec![],
+                                    aliases: vec![],
                                 },
                             },
                         ],
                         packed_dimensions: vec![],
                         unpacked_dimensions: vec![],
+                        aliases: vec!["mypack::bus_t".to_string()],
                     },
                 },
                 Port {
                     dir: PortDir::Output,
                     name: "data".to_string(),
+                    kind: Some(PortKind::Variable),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Enum {
                         name: "mypack::enum_t".to_string(),
                         variants: vec![
                             Variant {
                                 name: "A".to_string(),
                                 width: 16,
+                                signed: false,
                                 value: BigInt::from(1234),
                             },
                             Variant {
                                 name: "B".to_string(),
                                 width: 16,
+                                signed: false,
                                 value: BigInt::from(2345),
                             },
                         ],
                         packed_dimensions: vec![],
                         unpacked_dimensions: vec![],
+                        aliases: vec!["mypack::enum_t".to_string()],
                     },
                 }
             ]
@@ -532,31 +765,47 @@ mod tests {
                 Port {
                     dir: PortDir::Input,
                     name: "clk".to_string(),
+                    kind: Some(PortKind::Net {
+                        net_type: "wire".to_string(),
+                    }),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Logic {
                         signed: false,
                         packed_dimensions: vec![],
                         unpacked_dimensions: vec![],
+                        aliases: vec![],
                     },
                 },
                 Port {
                     dir: PortDir::Output,
                     name: "data".to_string(),
+                    kind: Some(PortKind::Variable),
+                    default: None,
+                    expression: None,
+                    location: None,
+                    attributes: vec![],
                     ty: Type::Enum {
                         name: "enum_t".to_string(),
                         variants: vec![
                             Variant {
                                 name: "A".to_string(),
                                 width: 16,
+                                signed: false,
                                 value: BigInt::from(1234),
                             },
                             Variant {
                                 name: "B".to_string(),
                                 width: 16,
+                                signed: false,
                                 value: BigInt::from(2345),
                             },
                         ],
                         packed_dimensions: vec![],
                         unpacked_dimensions: vec![],
+                        aliases: vec!["enum_t".to_string()],
                     },
                 },
             ]
@@ -702,10 +951,18 @@ endmodule
             vec![Port {
                 dir: PortDir::Input,
                 name: "clk".to_string(),
+                kind: Some(PortKind::Net {
+                    net_type: "wire".to_string(),
+                }),
+                default: None,
+                expression: None,
+                location: None,
+                attributes: vec![],
                 ty: Type::Logic {
                     signed: false,
                     packed_dimensions: vec![],
-                    unpacked_dimensions: vec![]
+                    unpacked_dimensions: vec![],
+                    aliases: vec![],
                 },
             }]
         );
@@ -736,10 +993,18 @@ endmodule
             vec![Port {
                 dir: PortDir::Input,
                 name: "a".to_string(),
+                kind: Some(PortKind::Net {
+                    net_type: "wire".to_string(),
+                }),
+                default: None,
+                expression: None,
+                location: None,
+                attributes: vec![],
                 ty: Type::Logic {
                     signed: false,
                     packed_dimensions: vec![],
                     unpacked_dimensions: vec![],
+                    aliases: vec![],
                 },
             }]
         );
@@ -795,10 +1060,18 @@ endmodule
             vec![Port {
                 dir: PortDir::Input,
                 name: "a".to_string(),
+                kind: Some(PortKind::Net {
+                    net_type: "wire".to_string(),
+                }),
+                default: None,
+                expression: None,
+                location: None,
+                attributes: vec![],
                 ty: Type::Logic {
                     signed: false,
                     packed_dimensions: vec![Range { msb: -1, lsb: 0 }],
                     unpacked_dimensions: vec![],
+                    aliases: vec![],
                 },
             },]
         );
@@ -868,38 +1141,358 @@ endmodule
         assert_eq!(parameters["foo"][0].name, "IntParam");
         assert_eq!(
             parameters["foo"][0].ty,
-            Type::Logic {
+            Some(Type::Logic {
                 signed: true,
                 packed_dimensions: vec![Range { msb: 31, lsb: 0 }],
                 unpacked_dimensions: vec![],
-            }
+                aliases: vec![],
+            })
         );
         assert_eq!(parameters["foo"][1].name, "UnsignedParam");
         assert_eq!(
             parameters["foo"][1].ty,
-            Type::Logic {
+            Some(Type::Logic {
                 signed: false,
                 packed_dimensions: vec![Range { msb: 31, lsb: 0 }],
                 unpacked_dimensions: vec![],
-            }
+                aliases: vec![],
+            })
         );
         assert_eq!(parameters["foo"][2].name, "LongIntParam");
         assert_eq!(
             parameters["foo"][2].ty,
-            Type::Logic {
+            Some(Type::Logic {
                 signed: true,
                 packed_dimensions: vec![Range { msb: 63, lsb: 0 }],
                 unpacked_dimensions: vec![],
-            }
+                aliases: vec![],
+            })
         );
         assert_eq!(parameters["foo"][3].name, "BitParam");
         assert_eq!(
             parameters["foo"][3].ty,
-            Type::Logic {
+            Some(Type::Logic {
                 signed: false,
                 packed_dimensions: vec![],
                 unpacked_dimensions: vec![],
-            }
+                aliases: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn test_extract_type_parameters() {
+        let verilog = str2tmpfile(
+            "
+        module fifo #(
+            parameter type T = logic [7:0],
+            parameter int DEPTH = 4
+        ) (
+            input T din,
+            output T dout
+        );
+        endmodule",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            parameters: &[("DEPTH", "16")],
+            ..Default::default()
+        };
+
+        let parameters = extract_parameter_defs(&cfg, false);
+        assert_eq!(parameters["fifo"].len(), 2);
+        let byte = Type::Logic {
+            signed: false,
+            packed_dimensions: vec![Range { msb: 7, lsb: 0 }],
+            unpacked_dimensions: vec![],
+            aliases: vec![],
+        };
+        assert_eq!(parameters["fifo"][0].name, "T");
+        assert_eq!(parameters["fifo"][0].ty, Some(byte));
+        assert_eq!(parameters["fifo"][0].kind, ParameterKind::Type);
+        assert_eq!(parameters["fifo"][0].source, ParameterSource::Default);
+        assert!(parameters["fifo"][0].is_port);
+        assert!(!parameters["fifo"][0].is_local);
+        assert_eq!(parameters["fifo"][1].name, "DEPTH");
+        assert_eq!(parameters["fifo"][1].kind, ParameterKind::Value);
+        assert_eq!(parameters["fifo"][1].source, ParameterSource::CommandLine);
+    }
+
+    #[test]
+    fn test_port_type_compatibility() {
+        let producer = str2tmpfile(
+            "
+        package pkt_pkg;
+            typedef struct packed {
+                logic [7:0] len;
+                logic valid;
+            } pkt_t;
+        endpackage
+
+        module producer (
+            output pkt_pkg::pkt_t pkt
+        );
+        endmodule",
+        )
+        .unwrap();
+        let consumer = str2tmpfile(
+            "
+        package pkt_pkg;
+            typedef struct packed {
+                logic [11:0] len;
+                logic valid;
+            } pkt_t;
+        endpackage
+
+        module consumer (
+            input pkt_pkg::pkt_t pkt
+        );
+        endmodule",
+        )
+        .unwrap();
+
+        let producer_ports = extract_ports(
+            &SlangConfig {
+                sources: &[producer.path().to_str().unwrap()],
+                ..Default::default()
+            },
+            false,
+        );
+        let consumer_ports = extract_ports(
+            &SlangConfig {
+                sources: &[consumer.path().to_str().unwrap()],
+                ..Default::default()
+            },
+            false,
+        );
+
+        let output = &producer_ports["producer"][0].ty;
+        let input = &consumer_ports["consumer"][0].ty;
+        assert_eq!(output.relation(output), Ok(TypeRelation::Matching));
+        assert_eq!(
+            input.relation(output),
+            Ok(TypeRelation::AssignmentCompatible)
+        );
+        assert_eq!(
+            input.equivalent(output).unwrap_err().to_string(),
+            "field `len` width 12 vs 8"
+        );
+    }
+
+    #[test]
+    fn test_typedef_aliases() {
+        let verilog = str2tmpfile(
+            "
+        package pkg;
+            typedef logic [39:0] addr_t;
+            typedef addr_t paddr_t;
+        endpackage
+
+        module foo (
+            input pkg::addr_t a,
+            input pkg::paddr_t p,
+            input pkg::addr_t [1:0] pair
+        );
+        endmodule",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            ..Default::default()
+        };
+
+        let ports = &extract_ports(&cfg, false)["foo"];
+        assert_eq!(ports[0].ty.aliases(), &["pkg::addr_t"]);
+        assert_eq!(ports[0].ty.typedef_name(), Some("pkg::addr_t"));
+        assert_eq!(ports[0].ty.width().unwrap(), 40);
+
+        assert_eq!(ports[1].ty.aliases(), &["pkg::paddr_t", "addr_t"]);
+        assert_eq!(ports[1].ty.typedef_name(), Some("pkg::paddr_t"));
+        assert_eq!(ports[1].ty, ports[0].ty);
+
+        assert_eq!(ports[2].ty.typedef_name(), None);
+        assert_eq!(ports[2].ty.width().unwrap(), 80);
+    }
+
+    #[test]
+    fn test_source_locations() {
+        let verilog = str2tmpfile(
+            "module foo #(
+    parameter int W = 4
+) (
+    input logic [W-1:0] a
+);
+    bar u_bar();
+endmodule
+module bar;
+endmodule
+package pkg;
+endpackage
+",
+        )
+        .unwrap();
+        let path = verilog.path().to_str().unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[path],
+            include_source_info: true,
+            ..Default::default()
+        };
+        let port = &extract_ports(&cfg, false)["foo"][0];
+        let location = port.location.as_ref().unwrap();
+        assert!(path.ends_with(&location.file) || location.file.ends_with(path));
+        assert_eq!((location.line, location.column), (4, 25));
+
+        let parameter = &extract_parameter_defs(&cfg, false)["foo"][0];
+        assert_eq!(parameter.location.as_ref().unwrap().line, 2);
+
+        let hierarchy = extract_hierarchy(&cfg).unwrap();
+        let u_bar = hierarchy["foo"].contents[0].borrow();
+        let location = u_bar.location.as_ref().unwrap();
+        assert_eq!((location.line, location.column), (6, 9));
+
+        let packages = extract_packages(&cfg).unwrap();
+        let location = packages["pkg"].location.as_ref().unwrap();
+        assert_eq!((location.line, location.column), (10, 9));
+
+        let cfg = SlangConfig {
+            sources: &[path],
+            ..Default::default()
+        };
+        assert_eq!(extract_ports(&cfg, false)["foo"][0].location, None);
+    }
+
+    #[test]
+    fn test_attributes() {
+        let verilog = str2tmpfile(
+            "
+        (* dont_touch *)
+        module foo #(
+            (* tunable = \"yes\" *) parameter int W = 4
+        ) (
+            (* mark_debug *) input logic [W-1:0] a,
+            output logic b
+        );
+            (* keep = 2 *) bar u_bar();
+        endmodule
+        module bar;
+        endmodule",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            tops: &["foo"],
+            ..Default::default()
+        };
+
+        let one = Some(ConstValue::integer(1, false, 1));
+        let ports = &extract_ports(&cfg, false)["foo"];
+        assert_eq!(
+            ports[0].attributes,
+            vec![("mark_debug".to_string(), one.clone())]
+        );
+        assert!(ports[1].attributes.is_empty());
+
+        let parameters = &extract_parameter_defs(&cfg, false)["foo"];
+        assert_eq!(
+            parameters[0].attributes,
+            vec![(
+                "tunable".to_string(),
+                Some(ConstValue::String("yes".to_string()))
+            )]
+        );
+
+        let hierarchy = extract_hierarchy(&cfg).unwrap();
+        let u_bar = hierarchy["foo"].contents[0].borrow();
+        assert_eq!(
+            u_bar.attributes,
+            vec![("keep".to_string(), Some(ConstValue::integer(32, true, 2)))]
+        );
+
+        let modules = extract_module_attributes(&cfg).unwrap();
+        assert_eq!(modules["foo"], vec![("dont_touch".to_string(), one)]);
+        assert!(modules["bar"].is_empty());
+    }
+
+    #[test]
+    fn test_parameter_declaration_kinds() {
+        let verilog = str2tmpfile(
+            "
+        module foo #(
+            parameter int W = 4
+        );
+            localparam int D = W * 2;
+        endmodule",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            parameters: &[("W", "6")],
+            ..Default::default()
+        };
+
+        let parameters = &extract_parameter_defs(&cfg, false)["foo"];
+        let [w, d] = &parameters[..] else {
+            panic!("expected two parameters");
+        };
+        assert!(w.is_port && !w.is_local);
+        assert_eq!(w.source, ParameterSource::CommandLine);
+        assert_eq!(w.value.as_deref(), Some("6"));
+        assert!(!d.is_port && d.is_local);
+        assert_eq!(d.source, ParameterSource::Default);
+        assert_eq!(d.value.as_deref(), Some("12"));
+    }
+
+    #[test]
+    fn test_parameter_const_values() {
+        let verilog = str2tmpfile(
+            "
+        module foo #(
+            parameter logic [7:0] MASK = 8'hff,
+            parameter logic [3:0] UNKNOWN = 4'b10xz,
+            parameter real RATIO = 1.5,
+            parameter string NAME = \"fifo\"
+        );
+            typedef struct {
+                int a;
+                int b;
+            } pair_t;
+            localparam pair_t PAIR = '{a: 1, b: 2};
+        endmodule",
+        )
+        .unwrap();
+
+        let cfg = SlangConfig {
+            sources: &[verilog.path().to_str().unwrap()],
+            ..Default::default()
+        };
+
+        let parameters = &extract_parameter_defs(&cfg, false)["foo"];
+        let [mask, unknown, ratio, name, pair] = &parameters[..] else {
+            panic!("expected five parameters");
+        };
+        let mask = mask.const_value().unwrap();
+        assert_eq!(mask, ConstValue::integer(8, false, 255));
+        assert_eq!(u64::try_from(&mask).unwrap(), 255);
+        assert!(u64::try_from(&unknown.const_value().unwrap()).is_err());
+        assert_eq!(ratio.ty, None);
+        assert_eq!(ratio.const_value().unwrap(), ConstValue::Real(1.5));
+        assert_eq!(name.ty, None);
+        assert_eq!(
+            name.const_value().unwrap(),
+            ConstValue::String("fifo".to_string())
+        );
+        assert_eq!(
+            pair.const_value().unwrap(),
+            ConstValue::Struct(vec![
+                ("a".to_string(), ConstValue::integer(32, true, 1)),
+                ("b".to_string(), ConstValue::integer(32, true, 2)),
+            ])
         );
     }
 }
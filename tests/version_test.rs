@@ -49,7 +49,7 @@ fn fetch_latest_version(crate_name: &str) -> Result<String, Box<dyn std::error::
 
 /// Fetches the local version of a package given the path to a `Cargo.toml`
 /// file.
-fn fetch_local_version(dirpath: &std::path::PathBuf) -> Result<String, Box<dyn std::error::Error>> {
+fn fetch_local_version(dirpath: &std::path::Path) -> Result<String, Box<dyn std::error::Error>> {
     let cargo_toml = std::fs::read_to_string(dirpath.join("Cargo.toml"))?;
     let cargo_toml: toml::Value = toml::from_str(&cargo_toml)?;
     let version = cargo_toml["package"]["version"]
@@ -64,7 +64,7 @@ fn fetch_local_version(dirpath: &std::path::PathBuf) -> Result<String, Box<dyn s
 
 fn validate_local_version_gt_released(
     crate_name: &str,
-    workspace_path: &std::path::PathBuf,
+    workspace_path: &std::path::Path,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let latest_version = fetch_latest_version(crate_name)?;
     let local_version = fetch_local_version(workspace_path)?;
@@ -82,13 +82,10 @@ fn validate_local_version_gt_released(
     if local_semver <= latest_semver {
         // Technically we're abusing io::Error a bit here just to avoid creating a whole
         // new error type.
-        Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!(
-                "Local version {} is not greater than the latest version {}",
-                local_version, latest_version
-            ),
-        )))
+        Err(Box::new(std::io::Error::other(format!(
+            "Local version {} is not greater than the latest version {}",
+            local_version, latest_version
+        ))))
     } else {
         Ok(())
     }